@@ -1,28 +1,33 @@
-use image::imageops::crop_imm;
 use nzm_visual::constants::*;
 use nzm_visual::recognizer;
 
+//NOTE: just binarize with ATTACK_BINARIZER, the glyph images needs to be manully cropped and saved as attack_{glyph}.png
+//NOTE: local binarizers need ATTACK_BINARIZER.context() pixels of background around each crop, see README
+
 fn main() {
-    std::fs::create_dir_all(ATTACK_LEVELS_TEMPLATES_DIR)
+    std::fs::create_dir_all(ATTACK_GLYPH_TEMPLATES_DIR)
         .expect("failed to create directory to save results");
 
-    for i in ATTACK_LEVELS {
-        if let Ok(source) = image::open(format!("{i}.png")) {
-            let binary = recognizer::otsu_binarize(
-                &crop_imm(&source, 160, 1446, 205 - 160, 1457 - 1446).to_image(),
-            );
+    for glyph in ATTACK_GLYPHS {
+        let stem = recognizer::glyph_file_stem(glyph);
+        if let Ok(source) = image::open(format!("attack_{stem}.png")) {
+            let Some(binary) = ATTACK_BINARIZER.binarize_template(&source) else {
+                eprintln!(
+                    "attack_{stem}.png is too small for a {} pixel background margin",
+                    ATTACK_BINARIZER.context()
+                );
+                continue;
+            };
             if binary
-                .save(format!(
-                    "{ATTACK_LEVELS_TEMPLATES_DIR}/{i}.png",
-                ))
+                .save(format!("{ATTACK_GLYPH_TEMPLATES_DIR}/{stem}.png"))
                 .is_ok()
             {
-                println!("template {i}.png done")
+                println!("template {stem}.png done")
             } else {
-                eprintln!("failed to save template {i}.png");
+                eprintln!("failed to save template {stem}.png");
             }
         } else {
-            eprintln!("failed to open source {i}.png");
+            eprintln!("failed to open source attack_{stem}.png");
         }
     }
 }
@@ -1,6 +1,9 @@
+#[cfg(windows)]
 use dxgi_capture_rs::DXGIManager;
+#[cfg(windows)]
 use image::{RgbImage};
 
+#[cfg(windows)]
 fn screenshot() {
     println!("初始化DXGI...");
     let mut manager = DXGIManager::new(5000).unwrap();
@@ -31,6 +34,12 @@ fn screenshot() {
     }
 }
 
+#[cfg(windows)]
 fn main() {
     screenshot();
 }
+
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("截图仅支持Windows");
+}
@@ -1,19 +1,31 @@
+use nzm_visual::constants::*;
 use nzm_visual::recognizer;
 
-//NOTE: just binarize, the digit images needs to be manully cropped
+//NOTE: just binarize with MONEY_BINARIZER, the digit images needs to be manully cropped and saved as {glyph}.png, the separator as comma.png
+//NOTE: local binarizers need MONEY_BINARIZER.context() pixels of background around each crop, see README
 
 fn main() {
-    let dir = "./money_digit_templates";
-    std::fs::create_dir_all(dir).expect("failed to create directory to save results");
+    std::fs::create_dir_all(MONEY_DIGIT_TEMPLATES_DIR)
+        .expect("failed to create directory to save results");
 
-    for i in 0..10 {
-        if let Ok(source) = image::open(format!("{i}.png")) {
-            let binary = recognizer::otsu_binarize(&source);
-            if binary.save(format!("{dir}/{i}.png", )).is_err() {
-                eprintln!("failed to save template {i}.png");
+    for glyph in MONEY_GLYPHS {
+        let stem = recognizer::glyph_file_stem(glyph);
+        if let Ok(source) = image::open(format!("{stem}.png")) {
+            let Some(binary) = MONEY_BINARIZER.binarize_template(&source) else {
+                eprintln!(
+                    "{stem}.png is too small for a {} pixel background margin",
+                    MONEY_BINARIZER.context()
+                );
+                continue;
+            };
+            if binary
+                .save(format!("{MONEY_DIGIT_TEMPLATES_DIR}/{stem}.png"))
+                .is_err()
+            {
+                eprintln!("failed to save template {stem}.png");
             }
         } else {
-            eprintln!("failed to open {i}.png")
+            eprintln!("failed to open {stem}.png")
         }
     }
 }
@@ -0,0 +1,210 @@
+use std::path::Path;
+
+use nzm_visual::capturer::animation::Animation;
+use nzm_visual::capturer::replay::{ReplayOrder, Replayer};
+use nzm_visual::capturer::watch::Watcher;
+use nzm_visual::capturer::{CaptureError, FrameSource};
+use nzm_visual::constants::MATCH_SHIFT_WINDOW;
+use nzm_visual::matcher::{DistanceMatcher, Matcher, NccMatcher};
+use nzm_visual::pipeline::Pipeline;
+use nzm_visual::recognizer::{Recognizer, TemplateDir};
+use nzm_visual::recorder::Recorder;
+
+//NOTE: usage: replay <screenshots dir | gif/apng file> [--by-time] [--fps <rate>] [--watch] [--record <session dir>]
+//             [--money-matcher <distance|ncc>] [--attack-matcher <distance|ncc>] [--shift <pixels>]
+
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+#[derive(Clone, Copy)]
+enum MatcherKind {
+    Distance,
+    Ncc,
+}
+
+fn parse_matcher(name: Option<String>) -> Option<MatcherKind> {
+    match name.as_deref() {
+        Some("distance") => Some(MatcherKind::Distance),
+        Some("ncc") => Some(MatcherKind::Ncc),
+        _ => {
+            eprintln!("匹配方式应为 distance 或 ncc");
+            None
+        }
+    }
+}
+
+struct Options {
+    dir: String,
+    order: ReplayOrder,
+    fps: Option<f64>,
+    watch: bool,
+    recorder: Option<Recorder>,
+}
+
+fn run<S: FrameSource, M: Matcher, A: Matcher>(
+    source: S,
+    current: impl Fn(&S) -> Option<&Path>,
+    recognizer: Recognizer<M, A>,
+    mut recorder: Option<Recorder>,
+) {
+    let mut pipeline = Pipeline::new(source, recognizer);
+    loop {
+        let tick = match pipeline.tick() {
+            Ok(tick) => tick,
+            Err(CaptureError::Exhausted) => break,
+            Err(CaptureError::Timeout) => {
+                std::thread::sleep(WATCH_INTERVAL);
+                continue;
+            }
+            Err(e @ CaptureError::Fatal(_)) => {
+                eprintln!("{e}");
+                break;
+            }
+            // NOTE: e.g. the watched folder was removed, keep retrying without spinning
+            Err(e) => {
+                eprintln!("{e}");
+                std::thread::sleep(WATCH_INTERVAL);
+                continue;
+            }
+        };
+        let name = current(pipeline.source()).unwrap().display();
+        let future = match tick.future {
+            Some((money, attack)) => format!("金币:{} 攻击力:{}", money, attack),
+            None => "无法识别".to_string(),
+        };
+        println!(
+            "#{} {name}: 当前: 金币:{} 攻击力:{} 最终: {} 耗时: {:?}",
+            tick.sequence, tick.money, tick.attack, future, tick.duration
+        );
+
+        if let Some(anomaly) = tick.anomaly {
+            println!("异常: {:?}", anomaly);
+        }
+
+        if let Some(recorder) = recorder.as_mut()
+            && let Err(e) = recorder.record(&tick)
+        {
+            eprintln!("录制失败: {:?}", e);
+        }
+    }
+
+    let stats = pipeline.stats();
+    println!(
+        "共{}帧, 丢帧: {}, 失败: {}",
+        stats.captured, stats.dropped, stats.failed
+    );
+}
+
+fn replay<M: Matcher, A: Matcher>(options: Options, money_matcher: M, attack_matcher: A) {
+    let Options {
+        dir,
+        order,
+        fps,
+        watch,
+        recorder,
+    } = options;
+
+    let recognizer =
+        match Recognizer::with_matchers(&TemplateDir::default(), money_matcher, attack_matcher) {
+            Ok(recognizer) => recognizer,
+            Err(e) => {
+                eprintln!("{e}");
+                return;
+            }
+        };
+    if !recognizer.separators() {
+        eprintln!("未找到逗号模板comma.png, 金币识别不检查千位分组");
+    }
+
+    if Path::new(&dir).is_file() {
+        match Animation::open(&dir) {
+            Ok(animation) => run(
+                animation,
+                |animation| Some(animation.path()),
+                recognizer,
+                recorder,
+            ),
+            Err(e) => eprintln!("无法读取动图 {dir}: {:?}", e),
+        }
+        return;
+    }
+
+    if watch {
+        match Watcher::new(&dir) {
+            Ok(watcher) => run(watcher, Watcher::current, recognizer, recorder),
+            Err(e) => eprintln!("无法读取文件夹 {dir}: {:?}", e),
+        }
+        return;
+    }
+
+    let mut replayer = match Replayer::new(&dir, order) {
+        Ok(replayer) => replayer,
+        Err(e) => {
+            eprintln!("无法读取文件夹 {dir}: {:?}", e);
+            return;
+        }
+    };
+    if let Some(fps) = fps.filter(|&fps| fps > 0.0) {
+        replayer = replayer.with_frame_rate(fps);
+    }
+    run(replayer, Replayer::current, recognizer, recorder);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(dir) = args.next() else {
+        eprintln!(
+            "用法: replay <截图文件夹|GIF/APNG动图> [--by-time] [--fps <帧率>] [--watch] [--record <录制文件夹>] [--money-matcher <distance|ncc>] [--attack-matcher <distance|ncc>] [--shift <像素>]"
+        );
+        return;
+    };
+
+    let mut options = Options {
+        dir,
+        order: ReplayOrder::Name,
+        fps: None,
+        watch: false,
+        recorder: None,
+    };
+    let (mut money_matcher, mut attack_matcher) = (MatcherKind::Distance, MatcherKind::Distance);
+    let mut window = MATCH_SHIFT_WINDOW;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--by-time" => options.order = ReplayOrder::Timestamp,
+            "--fps" => options.fps = args.next().and_then(|rate| rate.parse::<f64>().ok()),
+            "--watch" => options.watch = true,
+            "--record" => match args.next().map(Recorder::create) {
+                Some(Ok(session)) => options.recorder = Some(session),
+                Some(Err(e)) => eprintln!("无法创建录制文件夹: {:?}", e),
+                None => eprintln!("--record 需要指定文件夹"),
+            },
+            "--money-matcher" => {
+                money_matcher = parse_matcher(args.next()).unwrap_or(money_matcher)
+            }
+            "--attack-matcher" => {
+                attack_matcher = parse_matcher(args.next()).unwrap_or(attack_matcher)
+            }
+            "--shift" => match args.next().and_then(|pixels| pixels.parse::<u32>().ok()) {
+                Some(pixels) => window = pixels,
+                None => eprintln!("--shift 需要指定像素数"),
+            },
+            _ => eprintln!("忽略未知参数 {arg}"),
+        }
+    }
+
+    match (money_matcher, attack_matcher) {
+        (MatcherKind::Distance, MatcherKind::Distance) => replay(
+            options,
+            DistanceMatcher { window },
+            DistanceMatcher { window },
+        ),
+        (MatcherKind::Distance, MatcherKind::Ncc) => {
+            replay(options, DistanceMatcher { window }, NccMatcher { window })
+        }
+        (MatcherKind::Ncc, MatcherKind::Distance) => {
+            replay(options, NccMatcher { window }, DistanceMatcher { window })
+        }
+        (MatcherKind::Ncc, MatcherKind::Ncc) => {
+            replay(options, NccMatcher { window }, NccMatcher { window })
+        }
+    }
+}
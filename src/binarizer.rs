@@ -0,0 +1,287 @@
+use image::{GenericImageView, GrayImage, Luma, Pixel, imageops};
+
+fn otsu_threshold(gray: &GrayImage) -> u8 {
+    let mut hist = [0u32; 256];
+    let gray_data = gray.as_raw();
+    for &pixel in gray_data {
+        hist[pixel as usize] += 1;
+    }
+
+    let total_pixels = gray_data.len() as u32;
+
+    let mut cum_pixels = [0u32; 256];
+    let mut cum_sum = [0u64; 256];
+    cum_pixels[0] = hist[0];
+    cum_sum[0] = 0;
+
+    for i in 1..256 {
+        cum_pixels[i] = cum_pixels[i - 1] + hist[i];
+        cum_sum[i] = cum_sum[i - 1] + (i as u64) * (hist[i] as u64);
+    }
+
+    let total_sum = cum_sum[255];
+
+    let mut best_threshold = 0;
+    let mut max_variance = 0.0;
+
+    for t in 0..256 {
+        let pixels_bg = cum_pixels[t];
+        let pixels_fg = total_pixels - pixels_bg;
+
+        if pixels_bg == 0 || pixels_fg == 0 {
+            continue;
+        }
+
+        let sum_bg = cum_sum[t];
+        let sum_fg = total_sum - sum_bg;
+
+        let mean_bg = sum_bg as f64 / pixels_bg as f64;
+        let mean_fg = sum_fg as f64 / pixels_fg as f64;
+
+        let variance = (pixels_bg as f64) * (pixels_fg as f64) * (mean_bg - mean_fg).powi(2);
+
+        if variance > max_variance {
+            max_variance = variance;
+            best_threshold = t;
+        }
+    }
+
+    best_threshold as u8
+}
+
+pub fn otsu_binarize<T>(img: &T) -> GrayImage
+where
+    T: GenericImageView,
+    T::Pixel: Pixel<Subpixel = u8>,
+{
+    let gray: GrayImage = imageops::grayscale(img);
+    let threshold = otsu_threshold(&gray);
+
+    let gray_data = gray.as_raw();
+    let binary_data: Vec<u8> = gray_data
+        .iter()
+        .map(|&pixel| if pixel > threshold { 255 } else { 0 })
+        .collect();
+
+    GrayImage::from_vec(img.width(), img.height(), binary_data)
+        .expect("Failed to create binary image")
+}
+
+pub fn fixed_binarize(gray: &GrayImage, threshold: u8) -> GrayImage {
+    GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        Luma([if gray.get_pixel(x, y).0[0] > threshold {
+            255
+        } else {
+            0
+        }])
+    })
+}
+
+// NOTE: sums and squared sums over [0, x) x [0, y), one row and column larger than the image
+struct Integral {
+    stride: usize,
+    sums: Vec<u64>,
+    squares: Vec<u64>,
+}
+
+impl Integral {
+    fn new(gray: &GrayImage) -> Self {
+        let (width, height) = (gray.width() as usize, gray.height() as usize);
+        let stride = width + 1;
+        let mut sums = vec![0u64; stride * (height + 1)];
+        let mut squares = vec![0u64; stride * (height + 1)];
+        for y in 0..height {
+            let (mut row_sum, mut row_squares) = (0u64, 0u64);
+            for x in 0..width {
+                let pixel = gray.as_raw()[y * width + x] as u64;
+                row_sum += pixel;
+                row_squares += pixel * pixel;
+                let idx = (y + 1) * stride + x + 1;
+                sums[idx] = sums[idx - stride] + row_sum;
+                squares[idx] = squares[idx - stride] + row_squares;
+            }
+        }
+        Self {
+            stride,
+            sums,
+            squares,
+        }
+    }
+
+    fn area(
+        table: &[u64],
+        stride: usize,
+        (left, top, right, bottom): (usize, usize, usize, usize),
+    ) -> u64 {
+        table[bottom * stride + right] + table[top * stride + left]
+            - table[top * stride + right]
+            - table[bottom * stride + left]
+    }
+
+    // NOTE: mean and standard deviation over the rectangle, right and bottom exclusive
+    fn stats(&self, rect: (usize, usize, usize, usize)) -> (f32, f32) {
+        let (left, top, right, bottom) = rect;
+        let count = ((right - left) * (bottom - top)) as f32;
+        let mean = Self::area(&self.sums, self.stride, rect) as f32 / count;
+        let variance = Self::area(&self.squares, self.stride, rect) as f32 / count - mean * mean;
+        (mean, variance.max(0.0).sqrt())
+    }
+}
+
+// NOTE: the published formulas are for dark ink on light paper while the HUD is light on dark,
+// so the threshold is computed on the inverted image
+fn local_binarize(gray: &GrayImage, window: u32, threshold: impl Fn(f32, f32) -> f32) -> GrayImage {
+    let inverse = GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        Luma([255 - gray.get_pixel(x, y).0[0]])
+    });
+    let integral = Integral::new(&inverse);
+    let radius = window / 2;
+    GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        let rect = (
+            x.saturating_sub(radius) as usize,
+            y.saturating_sub(radius) as usize,
+            (x + radius + 1).min(gray.width()) as usize,
+            (y + radius + 1).min(gray.height()) as usize,
+        );
+        let (mean, deviation) = integral.stats(rect);
+        let ink = (inverse.get_pixel(x, y).0[0] as f32) < threshold(mean, deviation);
+        Luma([if ink { 255 } else { 0 }])
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorSpace {
+    Rgb,
+    Hsv,
+}
+
+// NOTE: hue in 0..1 turns, saturation and value in 0..1
+fn hsv([r, g, b]: [u8; 3]) -> [f32; 3] {
+    let [r, g, b] = [r, g, b].map(|c| c as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0) / 6.0
+    } else if max == g {
+        ((b - r) / delta + 2.0) / 6.0
+    } else {
+        ((r - g) / delta + 4.0) / 6.0
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    [hue, saturation, max]
+}
+
+// NOTE: both distances are normalized to 0..1,
+// hue is weighted by saturation since it means nothing for greys
+fn color_distance(a: [u8; 3], b: [u8; 3], space: ColorSpace) -> f32 {
+    match space {
+        ColorSpace::Rgb => {
+            let squares: f32 = a
+                .iter()
+                .zip(&b)
+                .map(|(&a, &b)| (a as f32 - b as f32).powi(2))
+                .sum();
+            squares.sqrt() / (255.0 * 3f32.sqrt())
+        }
+        ColorSpace::Hsv => {
+            let ([ha, sa, va], [hb, sb, vb]) = (hsv(a), hsv(b));
+            let turn = (ha - hb).abs();
+            let hue = turn.min(1.0 - turn) * 2.0 * sa.min(sb);
+            ((hue.powi(2) + (sa - sb).powi(2) + (va - vb).powi(2)) / 3.0).sqrt()
+        }
+    }
+}
+
+// NOTE: the dynamic range of the standard deviation in Sauvola's formula
+const SAUVOLA_RANGE: f32 = 128.0;
+
+// NOTE: ink is always 255 in the result, templates have to be generated with the same binarizer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Binarizer {
+    // NOTE: one global threshold per crop
+    Otsu,
+    // NOTE: pixels brighter than the threshold are ink
+    Fixed(u8),
+    // NOTE: local thresholds over a window x window neighbourhood, usually k = -0.2
+    Niblack {
+        window: u32,
+        k: f32,
+    },
+    // NOTE: like Niblack but quiet on flat background, usually k = 0.2..0.5
+    Sauvola {
+        window: u32,
+        k: f32,
+    },
+    // NOTE: pixels within tolerance (0..1) of the text color are ink, whatever their brightness
+    ColorKey {
+        color: [u8; 3],
+        tolerance: f32,
+        space: ColorSpace,
+    },
+}
+
+impl Binarizer {
+    pub fn binarize<T>(&self, img: &T) -> GrayImage
+    where
+        T: GenericImageView<Pixel: Pixel<Subpixel = u8>>,
+    {
+        match *self {
+            Binarizer::Otsu => otsu_binarize(img),
+            Binarizer::Fixed(threshold) => fixed_binarize(&imageops::grayscale(img), threshold),
+            Binarizer::Niblack { window, k } => {
+                local_binarize(&imageops::grayscale(img), window, |mean, deviation| {
+                    mean + k * deviation
+                })
+            }
+            Binarizer::Sauvola { window, k } => {
+                local_binarize(&imageops::grayscale(img), window, |mean, deviation| {
+                    mean * (1.0 + k * (deviation / SAUVOLA_RANGE - 1.0))
+                })
+            }
+            Binarizer::ColorKey {
+                color,
+                tolerance,
+                space,
+            } => GrayImage::from_fn(img.width(), img.height(), |x, y| {
+                let pixel = img.get_pixel(x, y).to_rgb().0;
+                let ink = color_distance(pixel, color, space) <= tolerance;
+                Luma([if ink { 255 } else { 0 }])
+            }),
+        }
+    }
+
+    // NOTE: the background a local threshold needs on every side of a glyph to see it the way
+    // it does within the whole region, a tight glyph crop has none
+    pub fn context(&self) -> u32 {
+        match *self {
+            Binarizer::Niblack { window, .. } | Binarizer::Sauvola { window, .. } => window / 2,
+            _ => 0,
+        }
+    }
+
+    // NOTE: the source is the tight glyph crop grown by context() on every side,
+    // that margin is cut off again after binarizing. None when the source is too small for it
+    pub fn binarize_template<T>(&self, source: &T) -> Option<GrayImage>
+    where
+        T: GenericImageView<Pixel: Pixel<Subpixel = u8>>,
+    {
+        let margin = self.context();
+        let (width, height) = source.dimensions();
+        if width <= margin * 2 || height <= margin * 2 {
+            return None;
+        }
+        let binary = self.binarize(source);
+        Some(
+            imageops::crop_imm(
+                &binary,
+                margin,
+                margin,
+                width - margin * 2,
+                height - margin * 2,
+            )
+            .to_image(),
+        )
+    }
+}
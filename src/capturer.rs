@@ -1,34 +1,107 @@
-use dxgi_capture_rs::DXGIManager;
-use image::{ImageBuffer, Rgba};
+use std::{fmt, path::Path, time::Instant};
 
-pub struct Capturer {
-    manager: DXGIManager,
+use image::{RgbaImage, imageops};
+
+pub mod animation;
+#[cfg(windows)]
+mod dxgi;
+pub mod recovery;
+pub mod replay;
+pub mod watch;
+
+#[cfg(windows)]
+pub use dxgi::Capturer;
+
+// NOTE: screenshot files, Steam saves jpg by default and NVIDIA png
+fn is_image(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        ["png", "jpg", "jpeg"]
+            .iter()
+            .any(|image| ext.eq_ignore_ascii_case(image))
+    })
 }
 
-impl Capturer {
-    pub fn new() -> Self {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Region {
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
         Self {
-            manager: DXGIManager::new(5000).unwrap(),
+            x,
+            y,
+            width,
+            height,
         }
     }
 
-    pub fn capture(&mut self) -> Option<ImageBuffer<Rgba<u8>, Vec<u8>>> {
-        match self.manager.capture_frame_components() {
-            Ok((pixels, (frame_width, frame_height))) => {
-                let mut screenshot = ImageBuffer::<Rgba<u8>, _>::from_raw(
-                    frame_width as u32,
-                    frame_height as u32,
-                    pixels,
-                )?;
-                for pix in screenshot.pixels_mut() {
-                    pix.0[..3].reverse();
-                }
-                Some(screenshot)
-            }
-            Err(e) => {
-                eprintln!("捕获失败: {:?}", e);
-                None
-            }
+    // NOTE: rounds outwards, so the scaled region never loses pixels of the original
+    pub fn scaled(&self, factor: f32) -> Self {
+        let left = (self.x as f32 * factor).floor();
+        let top = (self.y as f32 * factor).floor();
+        let right = ((self.x + self.width) as f32 * factor).ceil();
+        let bottom = ((self.y + self.height) as f32 * factor).ceil();
+        Self::new(
+            left as u32,
+            top as u32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        )
+    }
+}
+
+// NOTE: regions[i] is the crop of the i-th requested region,
+// timestamp is when the pixels were captured, sequence has gaps where the source dropped frames
+pub struct Frame {
+    pub regions: Vec<RgbaImage>,
+    pub timestamp: Instant,
+    pub sequence: u64,
+}
+
+impl Frame {
+    pub fn crop(image: &RgbaImage, regions: &[Region], timestamp: Instant, sequence: u64) -> Self {
+        Self {
+            regions: regions
+                .iter()
+                .map(|r| imageops::crop_imm(image, r.x, r.y, r.width, r.height).to_image())
+                .collect(),
+            timestamp,
+            sequence,
         }
     }
 }
+
+#[derive(Debug)]
+pub enum CaptureError {
+    // no new frame is available yet, simply try again
+    Timeout,
+    // the source is broken and has to be re-created
+    Lost(String),
+    // the source can not be recovered
+    Fatal(String),
+    // the source has no more frames
+    Exhausted,
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::Timeout => write!(f, "暂无新画面"),
+            CaptureError::Lost(reason) => write!(f, "捕获中断: {reason}"),
+            CaptureError::Fatal(reason) => write!(f, "捕获失败: {reason}"),
+            CaptureError::Exhausted => write!(f, "没有更多画面"),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+pub trait FrameSource {
+    fn geometry(&self) -> (u32, u32);
+
+    fn capture(&mut self, regions: &[Region]) -> Result<Frame, CaptureError>;
+}
@@ -0,0 +1,87 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use image::{
+    AnimationDecoder, DynamicImage, ImageFormat, ImageResult, RgbaImage,
+    codecs::{gif::GifDecoder, png::PngDecoder},
+};
+
+use super::{CaptureError, Frame, FrameSource, Region};
+
+// NOTE: plays back every frame of a GIF/APNG, timestamps advance by the embedded frame delays
+pub struct Animation {
+    path: PathBuf,
+    frames: VecDeque<(RgbaImage, Duration)>,
+    geometry: (u32, u32),
+    epoch: Instant,
+    sequence: u64,
+}
+
+fn decode(path: &Path) -> ImageResult<Vec<image::Frame>> {
+    let reader = || File::open(path).map(BufReader::new);
+    match ImageFormat::from_path(path)? {
+        ImageFormat::Gif => GifDecoder::new(reader()?)?.into_frames().collect_frames(),
+        ImageFormat::Png => {
+            let decoder = PngDecoder::new(reader()?)?;
+            if decoder.is_apng()? {
+                decoder.apng()?.into_frames().collect_frames()
+            } else {
+                let image = DynamicImage::from_decoder(decoder)?.to_rgba8();
+                Ok(vec![image::Frame::new(image)])
+            }
+        }
+        _ => image::open(path).map(|image| vec![image::Frame::new(image.to_rgba8())]),
+    }
+}
+
+impl Animation {
+    pub fn open(path: impl AsRef<Path>) -> ImageResult<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut offset = Duration::ZERO;
+        let mut frames = VecDeque::new();
+        for frame in decode(&path)? {
+            let delay = Duration::from(frame.delay());
+            frames.push_back((frame.into_buffer(), offset));
+            offset += delay;
+        }
+
+        let geometry = frames
+            .front()
+            .map_or((0, 0), |(image, _)| image.dimensions());
+        Ok(Self {
+            path,
+            frames,
+            geometry,
+            epoch: Instant::now(),
+            sequence: 0,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl FrameSource for Animation {
+    fn geometry(&self) -> (u32, u32) {
+        self.geometry
+    }
+
+    fn capture(&mut self, regions: &[Region]) -> Result<Frame, CaptureError> {
+        let (image, offset) = self.frames.pop_front().ok_or(CaptureError::Exhausted)?;
+
+        let sequence = self.sequence;
+        self.sequence += 1;
+        Ok(Frame::crop(&image, regions, self.epoch + offset, sequence))
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::Instant;
+
+use dxgi_capture_rs::{CaptureError as DXGICaptureError, DXGIManager};
+use image::RgbaImage;
+
+use super::{CaptureError, Frame, FrameSource, Region};
+
+pub struct Capturer {
+    manager: DXGIManager,
+    sequence: u64,
+}
+
+impl Capturer {
+    pub fn new() -> Result<Self, CaptureError> {
+        // NOTE: the output may come back later (display mode change, secure desktop), so keep retrying
+        let manager = DXGIManager::new(5000).map_err(|e| CaptureError::Lost(e.to_string()))?;
+        Ok(Self {
+            manager,
+            sequence: 0,
+        })
+    }
+}
+
+// NOTE: only the pixels inside the region are copied and swapped from BGRA to RGBA
+fn crop_bgra(pixels: &[u8], frame_width: usize, frame_height: usize, region: &Region) -> RgbaImage {
+    let x = (region.x as usize).min(frame_width);
+    let y = (region.y as usize).min(frame_height);
+    let width = (region.width as usize).min(frame_width - x);
+    let height = (region.height as usize).min(frame_height - y);
+
+    let mut raw = Vec::with_capacity(width * height * 4);
+    for row in y..y + height {
+        let begin = (row * frame_width + x) * 4;
+        raw.extend_from_slice(&pixels[begin..begin + width * 4]);
+    }
+    for pix in raw.chunks_exact_mut(4) {
+        pix.swap(0, 2);
+    }
+
+    RgbaImage::from_raw(width as u32, height as u32, raw).expect("Failed to create region image")
+}
+
+impl FrameSource for Capturer {
+    fn geometry(&self) -> (u32, u32) {
+        let (width, height) = self.manager.geometry();
+        (width as u32, height as u32)
+    }
+
+    fn capture(&mut self, regions: &[Region]) -> Result<Frame, CaptureError> {
+        match self.manager.capture_frame_components() {
+            Ok((pixels, (frame_width, frame_height))) => {
+                let timestamp = Instant::now();
+                if pixels.len() < frame_width * frame_height * 4 {
+                    return Err(CaptureError::Lost(format!(
+                        "画面数据不完整 {}x{}",
+                        frame_width, frame_height
+                    )));
+                }
+                self.sequence += 1;
+                Ok(Frame {
+                    regions: regions
+                        .iter()
+                        .map(|region| crop_bgra(&pixels, frame_width, frame_height, region))
+                        .collect(),
+                    timestamp,
+                    sequence: self.sequence - 1,
+                })
+            }
+            Err(DXGICaptureError::Timeout) => Err(CaptureError::Timeout),
+            Err(e) => Err(CaptureError::Lost(e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use super::{CaptureError, Frame, FrameSource, Region};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaptureState {
+    Healthy,
+    Retrying { attempt: u32, delay: Duration },
+    Failed,
+}
+
+impl fmt::Display for CaptureState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureState::Healthy => write!(f, "正常"),
+            CaptureState::Retrying { attempt, delay } => {
+                write!(f, "重试中(第{attempt}次, 间隔{delay:?})")
+            }
+            CaptureState::Failed => write!(f, "失败"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    // NOTE: None retries forever
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(200),
+            max: Duration::from_secs(10),
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << attempt.saturating_sub(1).min(16);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+}
+
+// NOTE: re-creates the source through the factory whenever it is lost
+pub struct Recovering<S, F>
+where
+    S: FrameSource,
+    F: FnMut() -> Result<S, CaptureError>,
+{
+    factory: F,
+    source: Option<S>,
+    backoff: Backoff,
+
+    state: CaptureState,
+    next_attempt: Instant,
+    geometry: (u32, u32),
+    // NOTE: first sequence number of the current source, and the one after the last frame
+    base_sequence: u64,
+    next_sequence: u64,
+}
+
+impl<S, F> Recovering<S, F>
+where
+    S: FrameSource,
+    F: FnMut() -> Result<S, CaptureError>,
+{
+    pub fn new(factory: F, backoff: Backoff) -> Self {
+        Self {
+            factory,
+            source: None,
+            backoff,
+            state: CaptureState::Healthy,
+            next_attempt: Instant::now(),
+            geometry: (0, 0),
+            base_sequence: 0,
+            next_sequence: 0,
+        }
+    }
+
+    pub fn state(&self) -> CaptureState {
+        self.state
+    }
+
+    fn fail(&mut self, error: CaptureError) -> CaptureError {
+        self.source = None;
+
+        self.state = match (&error, self.state) {
+            (CaptureError::Fatal(_), _) | (_, CaptureState::Failed) => CaptureState::Failed,
+            (_, state) => {
+                let attempt = match state {
+                    CaptureState::Retrying { attempt, .. } => attempt + 1,
+                    _ => 1,
+                };
+                if self.backoff.max_attempts.is_some_and(|max| attempt > max) {
+                    CaptureState::Failed
+                } else {
+                    let delay = self.backoff.delay(attempt);
+                    self.next_attempt = Instant::now() + delay;
+                    CaptureState::Retrying { attempt, delay }
+                }
+            }
+        };
+
+        error
+    }
+}
+
+impl<S, F> FrameSource for Recovering<S, F>
+where
+    S: FrameSource,
+    F: FnMut() -> Result<S, CaptureError>,
+{
+    fn geometry(&self) -> (u32, u32) {
+        self.source
+            .as_ref()
+            .map_or(self.geometry, |source| source.geometry())
+    }
+
+    fn capture(&mut self, regions: &[Region]) -> Result<Frame, CaptureError> {
+        if self.state == CaptureState::Failed {
+            return Err(CaptureError::Fatal("已停止重试".to_string()));
+        }
+
+        let source = match self.source.as_mut() {
+            Some(source) => source,
+            None => {
+                if Instant::now() < self.next_attempt {
+                    return Err(CaptureError::Timeout);
+                }
+                match (self.factory)() {
+                    Ok(source) => {
+                        self.geometry = source.geometry();
+                        self.base_sequence = self.next_sequence;
+                        self.source.insert(source)
+                    }
+                    Err(e) => return Err(self.fail(e)),
+                }
+            }
+        };
+
+        match source.capture(regions) {
+            Ok(mut frame) => {
+                // NOTE: re-created sources count from zero again
+                self.state = CaptureState::Healthy;
+                frame.sequence += self.base_sequence;
+                self.next_sequence = frame.sequence + 1;
+                Ok(frame)
+            }
+            Err(e @ (CaptureError::Timeout | CaptureError::Exhausted)) => Err(e),
+            Err(e) => Err(self.fail(e)),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use super::{CaptureError, Frame, FrameSource, Region, is_image};
+
+pub enum ReplayOrder {
+    Name,
+    Timestamp,
+}
+
+pub struct Replayer {
+    files: VecDeque<PathBuf>,
+    current: Option<PathBuf>,
+    geometry: (u32, u32),
+
+    interval: Option<Duration>,
+    epoch: Instant,
+    played: u32,
+    sequence: u64,
+}
+
+impl Replayer {
+    pub fn new(dir: impl AsRef<Path>, order: ReplayOrder) -> std::io::Result<Self> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if is_image(&path) && entry.file_type()?.is_file() {
+                files.push((entry.metadata()?.modified()?, path));
+            }
+        }
+
+        match order {
+            ReplayOrder::Name => files.sort_by(|a, b| a.1.cmp(&b.1)),
+            ReplayOrder::Timestamp => files.sort(),
+        }
+
+        let geometry = files
+            .first()
+            .and_then(|(_, path)| image::image_dimensions(path).ok())
+            .unwrap_or((0, 0));
+
+        Ok(Self {
+            files: files.into_iter().map(|(_, path)| path).collect(),
+            current: None,
+            geometry,
+            interval: None,
+            epoch: Instant::now(),
+            played: 0,
+            sequence: 0,
+        })
+    }
+
+    // NOTE: without a frame rate, frames are handed out as fast as they are requested,
+    // a rate that gives no valid interval (zero, negative, NaN) is ignored
+    pub fn with_frame_rate(mut self, fps: f64) -> Self {
+        if let Ok(interval) = Duration::try_from_secs_f64(1.0 / fps) {
+            self.interval = Some(interval);
+        }
+        self
+    }
+
+    pub fn current(&self) -> Option<&Path> {
+        self.current.as_deref()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.files.len()
+    }
+}
+
+impl FrameSource for Replayer {
+    fn geometry(&self) -> (u32, u32) {
+        self.geometry
+    }
+
+    fn capture(&mut self, regions: &[Region]) -> Result<Frame, CaptureError> {
+        while let Some(path) = self.files.pop_front() {
+            // NOTE: unreadable files still take a sequence number and show up as dropped frames
+            let sequence = self.sequence;
+            self.sequence += 1;
+
+            let image = match image::open(&path) {
+                Ok(image) => image.to_rgba8(),
+                Err(e) => {
+                    eprintln!("读取失败 {}: {:?}", path.display(), e);
+                    continue;
+                }
+            };
+
+            let timestamp = match self.interval {
+                Some(interval) => {
+                    if self.played == 0 {
+                        self.epoch = Instant::now();
+                    }
+                    let due = self.epoch + interval * self.played;
+                    std::thread::sleep(due.saturating_duration_since(Instant::now()));
+                    due
+                }
+                None => Instant::now(),
+            };
+
+            self.played += 1;
+            self.current = Some(path);
+            return Ok(Frame::crop(&image, regions, timestamp, sequence));
+        }
+
+        self.current = None;
+        Err(CaptureError::Exhausted)
+    }
+}
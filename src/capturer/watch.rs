@@ -0,0 +1,101 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use super::{CaptureError, Frame, FrameSource, Region, is_image};
+
+// NOTE: files that still fail to decode this long after their last write are given up
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+// NOTE: only files created after the watcher are fed, existing ones are ignored
+pub struct Watcher {
+    dir: PathBuf,
+    seen: HashSet<PathBuf>,
+    pending: VecDeque<PathBuf>,
+    current: Option<PathBuf>,
+    geometry: (u32, u32),
+    sequence: u64,
+}
+
+impl Watcher {
+    pub fn new(dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut watcher = Self {
+            dir: dir.as_ref().to_path_buf(),
+            seen: HashSet::new(),
+            pending: VecDeque::new(),
+            current: None,
+            geometry: (0, 0),
+            sequence: 0,
+        };
+        watcher.scan()?;
+        watcher.pending.clear();
+        Ok(watcher)
+    }
+
+    pub fn current(&self) -> Option<&Path> {
+        self.current.as_deref()
+    }
+
+    fn scan(&mut self) -> std::io::Result<()> {
+        let mut found = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if is_image(&path) && entry.file_type()?.is_file() && !self.seen.contains(&path) {
+                found.push((entry.metadata()?.modified()?, path));
+            }
+        }
+
+        found.sort();
+        for (_, path) in found {
+            self.seen.insert(path.clone());
+            self.pending.push_back(path);
+        }
+        Ok(())
+    }
+}
+
+impl FrameSource for Watcher {
+    fn geometry(&self) -> (u32, u32) {
+        self.geometry
+    }
+
+    fn capture(&mut self, regions: &[Region]) -> Result<Frame, CaptureError> {
+        self.scan()
+            .map_err(|e| CaptureError::Lost(format!("{}: {e}", self.dir.display())))?;
+
+        while let Some(path) = self.pending.front() {
+            let modified = std::fs::metadata(path).and_then(|meta| meta.modified());
+            let age = modified
+                .ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .unwrap_or_default();
+
+            let image = match image::open(path) {
+                Ok(image) => image.to_rgba8(),
+                // NOTE: probably still being written by the other tool
+                Err(_) if age < SETTLE_TIME => return Err(CaptureError::Timeout),
+                Err(e) => {
+                    eprintln!("读取失败 {}: {:?}", path.display(), e);
+                    self.pending.pop_front();
+                    self.sequence += 1;
+                    continue;
+                }
+            };
+
+            let path = self.pending.pop_front();
+            let sequence = self.sequence;
+            self.sequence += 1;
+
+            self.geometry = image.dimensions();
+            self.current = path;
+            let now = Instant::now();
+            let timestamp = now.checked_sub(age).unwrap_or(now);
+            return Ok(Frame::crop(&image, regions, timestamp, sequence));
+        }
+
+        Err(CaptureError::Timeout)
+    }
+}
@@ -1,26 +1,63 @@
+use crate::binarizer::Binarizer;
+use crate::capturer::Region;
+use crate::segmenter::Segmenter;
+
 pub const MONEY_DIGIT_TEMPLATES_DIR: &str = "./money_digit_templates/";
 
-pub const ATTACK_LEVELS_TEMPLATES_DIR: &str = "./attack_templates/";
+pub const ATTACK_GLYPH_TEMPLATES_DIR: &str = "./attack_templates/";
+
+pub const DUMP_DIR: &str = "./dumps/";
+
+pub const HISTORY_WINDOW: std::time::Duration = std::time::Duration::from_secs(10);
+
+// NOTE: screen size the regions below and the template pack were captured at,
+// the HUD scales with the screen height
+pub const REFERENCE_GEOMETRY: (u32, u32) = (2560, 1600);
+
+// NOTE: x, y, width, height of the cropped area on the screenshot
+pub const MONEY_REGION: Region = Region::new(67, 1387, 144 - 66, 1402 - 1387);
+
+pub const ATTACK_REGION: Region = Region::new(160, 1446, 205 - 160, 1457 - 1446);
+
+// NOTE: shared by the recognizer and the template generation binaries,
+// regenerate the templates after changing either of these
+pub const MONEY_BINARIZER: Binarizer = Binarizer::Otsu;
+pub const ATTACK_BINARIZER: Binarizer = Binarizer::Otsu;
+
+pub const MONEY_SEGMENTER: Segmenter = Segmenter::Projection;
+pub const ATTACK_SEGMENTER: Segmenter = Segmenter::Projection;
+
+// NOTE: max mean luma difference of a fingerprint cell for a region to count as unchanged
+pub const REGION_CHANGE_TOLERANCE: u8 = 8;
 
-pub const MONEY_DIGITS: std::ops::Range<u8> = 0..10;
+pub const MONEY_SEPARATOR: char = ',';
+
+pub const MONEY_GLYPHS: [char; 11] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ','];
+
+pub const MONEY_MAX_DIGITS: usize = 7;
+
+// NOTE: the most digits plus a separator before every third of them
+pub const MONEY_MAX_GLYPHS: usize = 9;
+
+// NOTE: ±pixels a glyph may be shifted from its template in x and y and still match
+pub const MATCH_SHIFT_WINDOW: u32 = 1;
+
+// NOTE: factors tried on top of the scale derived from the screen geometry, for the in-game UI scale
+pub const MATCH_SCALES: [f32; 5] = [0.8, 0.9, 1.0, 1.1, 1.25];
+
+// NOTE: a segment wider than the widest template by this factor is tried as touching glyphs
+pub const TOUCHING_WIDTH_RATIO: f32 = 1.3;
+
+// NOTE: readings with a glyph below either of these are reported as uncertain
+pub const GLYPH_PASSING_CONFIDENCE: f32 = 0.8;
+pub const GLYPH_PASSING_MARGIN: f32 = 0.05;
 
 pub const ATTACK_LEVELS: [u16; 29] = [
     25, 50, 75, 100, 125, 150, 175, 200, 225, 250, 275, 300, 325, 350, 375, 400, 425, 450, 475,
     500, 550, 600, 650, 700, 750, 800, 1000, 1200, 1500,
 ];
 
-pub const ATTACK_WIDTHS_LEVEL_1: u16 = 50;
-pub const ATTACK_WIDTHS_LEVEL_2: u16 = 450;
-pub const ATTACK_WIDTHS_LEVEL_3: u16 = 1000;
-pub const ATTACK_WIDTHS_LEVELS_NUM: [u16; 3] = [
-    ATTACK_WIDTHS_LEVEL_1,
-    ATTACK_WIDTHS_LEVEL_2,
-    ATTACK_WIDTHS_LEVEL_3
-];
+pub const ATTACK_GLYPHS: [char; 11] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '%'];
 
-pub const ATTACK_WIDTHS_LEVELS_1: [u16; 3] = [25, 50, 75];
-pub const ATTACK_WIDTHS_LEVELS_2: [u16; 23] = [
-    100, 125, 150, 175, 200, 225, 250, 275, 300, 325, 350, 375, 400, 425, 450, 475, 500, 550, 600,
-    650, 700, 750, 800,
-];
-pub const ATTACK_WIDTHS_LEVELS_3: [u16; 3] = [1000, 1200, 1500];
+// NOTE: the highest level has 4 digits plus the %
+pub const ATTACK_MAX_GLYPHS: usize = 5;
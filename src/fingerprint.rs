@@ -0,0 +1,44 @@
+use image::{GrayImage, RgbaImage, imageops};
+
+// NOTE: mean luma of every BLOCK x BLOCK cell, cheap enough to compute every tick
+pub struct Fingerprint {
+    width: u32,
+    height: u32,
+    cells: Vec<u8>,
+}
+
+impl Fingerprint {
+    pub const BLOCK: u32 = 3;
+
+    pub fn new(image: &RgbaImage) -> Self {
+        let gray: GrayImage = imageops::grayscale(image);
+        let (width, height) = gray.dimensions();
+        let (cols, rows) = (width.div_ceil(Self::BLOCK), height.div_ceil(Self::BLOCK));
+
+        let mut sums = vec![(0u32, 0u32); (cols * rows) as usize];
+        for (x, y, pixel) in gray.enumerate_pixels() {
+            let cell = &mut sums[((y / Self::BLOCK) * cols + x / Self::BLOCK) as usize];
+            cell.0 += pixel.0[0] as u32;
+            cell.1 += 1;
+        }
+
+        Self {
+            width,
+            height,
+            cells: sums
+                .iter()
+                .map(|&(sum, count)| (sum / count) as u8)
+                .collect(),
+        }
+    }
+
+    pub fn matches(&self, other: &Self, tolerance: u8) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self
+                .cells
+                .iter()
+                .zip(&other.cells)
+                .all(|(&a, &b)| a.abs_diff(b) <= tolerance)
+    }
+}
@@ -0,0 +1,59 @@
+use std::{
+    collections::VecDeque,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::pipeline::Tick;
+use crate::recorder::Recorder;
+
+// NOTE: keeps the ticks of the last `window`, measured on capture timestamps
+pub struct History {
+    window: Duration,
+    ticks: VecDeque<Tick>,
+}
+
+impl History {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            ticks: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, tick: Tick) {
+        while let Some(oldest) = self.ticks.front()
+            && tick.timestamp.saturating_duration_since(oldest.timestamp) > self.window
+        {
+            self.ticks.pop_front();
+        }
+        self.ticks.push_back(tick);
+    }
+
+    pub fn len(&self) -> usize {
+        self.ticks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+
+    // NOTE: writes a recorder session named after the last tick into `dir`,
+    // the buffer is kept so a later dump still holds the ticks leading up to it
+    pub fn dump(&self, dir: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let sequence = self.ticks.back().map_or(0, |tick| tick.sequence);
+        let since_epoch = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        let session = dir
+            .as_ref()
+            .join(format!("{}_{:08}", since_epoch.as_secs(), sequence));
+
+        let mut recorder = Recorder::create(&session)?;
+        for tick in &self.ticks {
+            recorder.record(tick)?;
+        }
+        Ok(session)
+    }
+}
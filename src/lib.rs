@@ -1,5 +1,13 @@
 pub mod constants;
+pub mod binarizer;
 pub mod capturer;
+pub mod fingerprint;
+pub mod history;
+pub mod matcher;
 pub mod recognizer;
 pub mod processor;
+#[cfg(windows)]
 pub mod overlayer;
+pub mod pipeline;
+pub mod recorder;
+pub mod segmenter;
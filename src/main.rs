@@ -1,45 +1,116 @@
-use image::imageops;
-use std::time::Instant;
-
-use nzm_visual::capturer;
+#[cfg(windows)]
+use nzm_visual::capturer::{
+    self, CaptureError, FrameSource,
+    recovery::{Backoff, CaptureState, Recovering},
+};
+#[cfg(windows)]
+use nzm_visual::constants::*;
+#[cfg(windows)]
+use nzm_visual::history::History;
+#[cfg(windows)]
 use nzm_visual::overlayer;
-use nzm_visual::processor;
-use nzm_visual::recognizer;
+#[cfg(windows)]
+use nzm_visual::pipeline::Pipeline;
+#[cfg(windows)]
+use nzm_visual::recognizer::{Recognizer, TemplateDir};
+#[cfg(windows)]
+use nzm_visual::recorder::Recorder;
 
-fn main() {
-    recognizer::initialize();
-    overlayer::Overlayer::initialize();
+// NOTE: unchanged regions reuse the previous result, so ticking often is cheap
+#[cfg(windows)]
+const TICK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
 
-    let mut capturer = capturer::Capturer::new();
-    let processor = processor::Processor::new();
-    let wnd = overlayer::Overlayer::new(100, 800, 300, 150);
+#[cfg(windows)]
+const ERROR_DISPLAY_TIME: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[cfg(windows)]
+fn run<S, F>(source: Recovering<S, F>, recognizer: Recognizer, mut recorder: Option<Recorder>)
+where
+    S: FrameSource,
+    F: FnMut() -> Result<S, CaptureError>,
+{
+    let mut pipeline = Pipeline::new(source, recognizer);
+    let mut history = History::new(HISTORY_WINDOW);
+    let mut anomalous = false;
+    let wnd = overlayer::Overlayer::new(100, 800, 300, 180);
     std::thread::sleep(std::time::Duration::from_secs(1)); // waiting for capture to initialize
 
     loop {
-        if let Some(screenshot) = capturer.capture() {
-            let start = Instant::now();
-
-            let money = recognizer::recognize_money(
-                &imageops::crop_imm(&screenshot, 67, 1387, 144 - 66, 1402 - 1387).to_image(),
-            );
-            let attack = recognizer::recognize_attack(
-                &imageops::crop_imm(&screenshot, 160, 1446, 205 - 160, 1457 - 1446).to_image(),
-            );
-
-            let duration = start.elapsed();
-
-            let future_data = processor.process(money, attack);
-
-            wnd.tick(
-                money.0,
-                money.1,
-                attack.0,
-                attack.1,
-                future_data.0,
-                future_data.1,
-                duration,
-            );
+        match pipeline.tick() {
+            Ok(tick) => {
+                wnd.tick(&tick, pipeline.stats(), pipeline.source().state());
+                if let Some(recorder) = recorder.as_mut()
+                    && let Err(e) = recorder.record(&tick)
+                {
+                    eprintln!("录制失败: {:?}", e);
+                }
+
+                if let Some(anomaly) = tick.anomaly {
+                    eprintln!("异常: {:?}", anomaly);
+                }
+                // NOTE: only where an anomaly starts, cached readings keep repeating it every tick
+                let dump = tick.anomaly.is_some() && !anomalous;
+                anomalous = tick.anomaly.is_some();
+                history.push(tick);
+                if dump || wnd.dump_requested() {
+                    match history.dump(DUMP_DIR) {
+                        Ok(session) => eprintln!("已保存最近画面至 {}", session.display()),
+                        Err(e) => eprintln!("保存最近画面失败: {:?}", e),
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                let state = pipeline.source().state();
+                if state != CaptureState::Healthy {
+                    wnd.status(state);
+                }
+            }
         }
-        std::thread::sleep(std::time::Duration::from_secs(1));
+        std::thread::sleep(TICK_INTERVAL);
     }
 }
+
+//NOTE: usage: nzm-visual [--record <session dir>]
+
+#[cfg(windows)]
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut recorder = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--record" => match args.next().map(Recorder::create) {
+                Some(Ok(session)) => recorder = Some(session),
+                Some(Err(e)) => eprintln!("无法创建录制文件夹: {:?}", e),
+                None => eprintln!("--record 需要指定文件夹"),
+            },
+            _ => eprintln!("忽略未知参数 {arg}"),
+        }
+    }
+
+    overlayer::Overlayer::initialize();
+    let recognizer = match Recognizer::new(&TemplateDir::default()) {
+        Ok(recognizer) => recognizer,
+        Err(e) => {
+            eprintln!("{e}");
+            let wnd = overlayer::Overlayer::new(100, 800, 300, 180);
+            wnd.message(&e.to_string());
+            std::thread::sleep(ERROR_DISPLAY_TIME);
+            return;
+        }
+    };
+    if !recognizer.separators() {
+        eprintln!("未找到逗号模板comma.png, 金币识别不检查千位分组");
+    }
+
+    run(
+        Recovering::new(capturer::Capturer::new, Backoff::default()),
+        recognizer,
+        recorder,
+    );
+}
+
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("悬浮显示仅支持Windows");
+}
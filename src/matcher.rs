@@ -0,0 +1,160 @@
+use image::{GrayImage, Luma};
+
+use crate::constants::MATCH_SHIFT_WINDOW;
+use crate::recognizer::{bidirectional_distance_transform, match_confidence, template_match};
+
+// NOTE: offset is how far the glyph sits from the template's position, in pixels of the resized crop
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Score {
+    pub confidence: f32,
+    pub offset: (i32, i32),
+}
+
+// NOTE: pixels shifted in from outside are the fill value, or the nearest edge pixel without one
+fn shift(image: &GrayImage, (dx, dy): (i32, i32), fill: Option<u8>) -> GrayImage {
+    let (width, height) = (image.width() as i32, image.height() as i32);
+    GrayImage::from_fn(image.width(), image.height(), |x, y| {
+        let (sx, sy) = (x as i32 + dx, y as i32 + dy);
+        let inside = (0..width).contains(&sx) && (0..height).contains(&sy);
+        match fill {
+            Some(fill) if !inside => Luma([fill]),
+            _ => *image.get_pixel(
+                sx.clamp(0, width - 1) as u32,
+                sy.clamp(0, height - 1) as u32,
+            ),
+        }
+    })
+}
+
+// NOTE: offsets are tried nearest first, so ties keep the smallest shift
+fn search(
+    window: u32,
+    crop: &GrayImage,
+    fill: Option<u8>,
+    confidence: impl Fn(&GrayImage) -> f32,
+) -> Score {
+    let window = window as i32;
+    let mut offsets: Vec<(i32, i32)> = (-window..=window)
+        .flat_map(|dy| (-window..=window).map(move |dx| (dx, dy)))
+        .collect();
+    offsets.sort_by_key(|(dx, dy)| dx.abs() + dy.abs());
+
+    let mut best = Score {
+        confidence: confidence(crop),
+        offset: (0, 0),
+    };
+    for offset in offsets.into_iter().skip(1) {
+        let confidence = confidence(&shift(crop, offset, fill));
+        if confidence > best.confidence {
+            best = Score { confidence, offset };
+        }
+    }
+    best
+}
+
+// NOTE: templates are always the binarized images from the template directories,
+// crops are resized to the template's dimensions before being compared
+pub trait Matcher {
+    type Template;
+
+    // NOTE: whether crops are handed over binarized or as grayscale
+    const BINARY: bool;
+
+    fn prepare(&self, binary: &GrayImage) -> Self::Template;
+
+    // NOTE: confidence 1 is a perfect match, 0 is no resemblance at all
+    fn score(&self, crop: &GrayImage, template: &Self::Template) -> Score;
+}
+
+// NOTE: window is the ±N pixels searched in x and y
+#[derive(Clone, Copy, Debug)]
+pub struct DistanceMatcher {
+    pub window: u32,
+}
+
+impl Default for DistanceMatcher {
+    fn default() -> Self {
+        Self {
+            window: MATCH_SHIFT_WINDOW,
+        }
+    }
+}
+
+impl Matcher for DistanceMatcher {
+    // fg_dist bg_dist
+    type Template = (GrayImage, GrayImage);
+
+    const BINARY: bool = true;
+
+    fn prepare(&self, binary: &GrayImage) -> Self::Template {
+        bidirectional_distance_transform(binary)
+    }
+
+    fn score(&self, crop: &GrayImage, (tmpl_fg, tmpl_bg): &Self::Template) -> Score {
+        search(self.window, crop, Some(0), |crop| {
+            template_match(crop, tmpl_fg, tmpl_bg)
+                .map_or(0.0, |score| match_confidence(score, tmpl_fg.len()))
+        })
+    }
+}
+
+// NOTE: pixels minus their mean, and the norm of that
+pub struct Centered {
+    values: Vec<f32>,
+    norm: f32,
+}
+
+impl Centered {
+    fn new(image: &GrayImage) -> Self {
+        let pixels = image.as_raw();
+        let mean = pixels.iter().map(|&p| p as f32).sum::<f32>() / pixels.len().max(1) as f32;
+        let values: Vec<f32> = pixels.iter().map(|&p| p as f32 - mean).collect();
+        let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+        Self { values, norm }
+    }
+}
+
+// NOTE: normalized cross-correlation on the grayscale crop, so it is unaffected by
+// the brightness and contrast of whatever is behind the HUD, anti-correlation counts as 0
+#[derive(Clone, Copy, Debug)]
+pub struct NccMatcher {
+    pub window: u32,
+}
+
+impl Default for NccMatcher {
+    fn default() -> Self {
+        Self {
+            window: MATCH_SHIFT_WINDOW,
+        }
+    }
+}
+
+impl Matcher for NccMatcher {
+    type Template = Centered;
+
+    const BINARY: bool = false;
+
+    fn prepare(&self, binary: &GrayImage) -> Self::Template {
+        Centered::new(binary)
+    }
+
+    fn score(&self, crop: &GrayImage, template: &Self::Template) -> Score {
+        search(self.window, crop, None, |crop| {
+            let crop = Centered::new(crop);
+            // NOTE: a crop of another size does not line up with the template, as in template_match
+            if crop.values.len() != template.values.len()
+                || crop.norm == 0.0
+                || template.norm == 0.0
+            {
+                return 0.0;
+            }
+            let dot: f32 = crop
+                .values
+                .iter()
+                .zip(&template.values)
+                .map(|(a, b)| a * b)
+                .sum();
+            (dot / (crop.norm * template.norm)).max(0.0)
+        })
+    }
+}
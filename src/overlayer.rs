@@ -1,11 +1,21 @@
+use std::cell::Cell;
+
 use windows::{
     Win32::{
-        Foundation::*, Graphics::Gdi::*, System::LibraryLoader::GetModuleHandleW,
-        UI::WindowsAndMessaging::*,
+        Foundation::*,
+        Graphics::Gdi::*,
+        System::LibraryLoader::GetModuleHandleW,
+        UI::{
+            Input::KeyboardAndMouse::{GetAsyncKeyState, VK_F9},
+            WindowsAndMessaging::*,
+        },
     },
     core::w,
 };
 
+use crate::capturer::recovery::CaptureState;
+use crate::pipeline::{FrameStats, Tick};
+
 pub struct Overlayer {
     hwnd: HWND,
 
@@ -16,6 +26,9 @@ pub struct Overlayer {
     mem_dc: HDC,
     bitmap: HBITMAP,
     bits: *mut u8,
+
+    // NOTE: whether F9 was down on the previous dump_requested call
+    dump_key: Cell<bool>,
 }
 
 impl Overlayer {
@@ -95,31 +108,45 @@ impl Overlayer {
                 mem_dc,
                 bitmap,
                 bits: bits as *mut u8,
+                dump_key: Cell::new(false),
             }
         }
     }
 
-    pub fn tick(
-        &self,
-        current_money: u32,
-        current_money_score: u32,
-        current_attack: u16,
-        current_attack_score: u32,
-        future_money: u32,
-        future_attack: u16,
-        duration: std::time::Duration,
-    ) {
+    pub fn tick(&self, tick: &Tick, stats: FrameStats, capture_state: CaptureState) {
+        let future = match tick.future {
+            Some((money, attack)) => format!("金币:{} 攻击力:{}", money, attack),
+            None => "无法识别".to_string(),
+        };
+        self.draw(&format!(
+            "当前: 金币:{} 攻击力:{}\n\n最终: {}\n\n耗时: {:?} 延迟: {:?}\n捕获: {} 丢帧: {} 失败: {}",
+            tick.money, tick.attack, future, tick.duration, tick.latency,
+            capture_state, stats.dropped, stats.failed
+        ));
+    }
+
+    // NOTE: F9 asks for a dump of the recent frames, once per press however long it is held
+    pub fn dump_requested(&self) -> bool {
+        let down = unsafe { GetAsyncKeyState(VK_F9.0 as i32) } < 0;
+        let was_down = self.dump_key.replace(down);
+        down && !was_down
+    }
+
+    pub fn status(&self, capture_state: CaptureState) {
+        self.draw(&format!("捕获: {}", capture_state));
+    }
+
+    pub fn message(&self, text: &str) {
+        self.draw(text);
+    }
+
+    fn draw(&self, text: &str) {
         unsafe {
             std::ptr::write_bytes(self.bits, 0, (self.width * self.height * 4) as usize);
 
             SetBkMode(self.mem_dc, TRANSPARENT);
             SetTextColor(self.mem_dc, COLORREF(0x00FFFFFF));
 
-            let text = format!(
-                "当前: 金币:{}({}) 攻击力:{}({})\n\n最终: 金币:{} 攻击力:{}\n\n耗时: {:?}",
-                current_money, current_money_score, current_attack, current_attack_score,
-                future_money, future_attack, duration
-            );
             let mut text_wide: Vec<u16> = text.encode_utf16().collect();
 
             let mut rect = RECT {
@@ -0,0 +1,191 @@
+use std::time::{Duration, Instant};
+
+use image::RgbaImage;
+
+use crate::capturer::{CaptureError, FrameSource};
+use crate::constants::*;
+use crate::fingerprint::Fingerprint;
+use crate::matcher::{DistanceMatcher, Matcher};
+use crate::processor::{Anomaly, Processor};
+use crate::recognizer::{Reading, Recognizer};
+
+#[derive(Clone)]
+pub struct Tick {
+    pub timestamp: Instant,
+    pub sequence: u64,
+    // NOTE: the money and attack crops the results were recognized from
+    pub regions: Vec<RgbaImage>,
+    pub money: Reading<u32>,
+    pub attack: Reading<u16>,
+    // NOTE: None until both fields have been read once
+    pub future: Option<(u32, u16)>,
+    // NOTE: recognition time only
+    pub duration: Duration,
+    // NOTE: from the moment the pixels were captured until the result is ready
+    pub latency: Duration,
+    pub anomaly: Option<Anomaly>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    pub captured: u64,
+    // NOTE: gaps in the sequence numbers handed out by the source
+    pub dropped: u64,
+    // NOTE: no new frame was available
+    pub skipped: u64,
+    pub failed: u64,
+    // NOTE: regions whose previous recognition result was reused
+    pub unchanged: u64,
+}
+
+struct Cached<T> {
+    fingerprint: Fingerprint,
+    result: T,
+}
+
+fn recognize_cached<T: Clone>(
+    cache: &mut Option<Cached<T>>,
+    region: &RgbaImage,
+    tolerance: Option<u8>,
+    unchanged: &mut u64,
+    recognize: impl FnOnce(&RgbaImage) -> T,
+) -> T {
+    let Some(tolerance) = tolerance else {
+        return recognize(region);
+    };
+
+    let fingerprint = Fingerprint::new(region);
+    if let Some(cached) = cache
+        && cached.fingerprint.matches(&fingerprint, tolerance)
+    {
+        *unchanged += 1;
+        return cached.result.clone();
+    }
+
+    let result = recognize(region);
+    *cache = Some(Cached {
+        fingerprint,
+        result: result.clone(),
+    });
+    result
+}
+
+// NOTE: the HUD scales with the screen height, an unknown geometry is taken as the reference
+pub fn geometry_scale((_, height): (u32, u32)) -> f32 {
+    if height == 0 {
+        return 1.0;
+    }
+    height as f32 / REFERENCE_GEOMETRY.1 as f32
+}
+
+pub struct Pipeline<S: FrameSource, M: Matcher = DistanceMatcher, A: Matcher = DistanceMatcher> {
+    source: S,
+    recognizer: Recognizer<M, A>,
+    processor: Processor,
+
+    stats: FrameStats,
+    next_sequence: Option<u64>,
+
+    // NOTE: None recognizes every region on every tick
+    change_tolerance: Option<u8>,
+    money_cache: Option<Cached<Reading<u32>>>,
+    attack_cache: Option<Cached<Reading<u16>>>,
+}
+
+impl<S: FrameSource, M: Matcher, A: Matcher> Pipeline<S, M, A> {
+    pub fn new(source: S, recognizer: Recognizer<M, A>) -> Self {
+        Self {
+            source,
+            recognizer,
+            processor: Processor::new(),
+            stats: FrameStats::default(),
+            next_sequence: None,
+            change_tolerance: Some(REGION_CHANGE_TOLERANCE),
+            money_cache: None,
+            attack_cache: None,
+        }
+    }
+
+    pub fn with_change_tolerance(mut self, tolerance: Option<u8>) -> Self {
+        self.change_tolerance = tolerance;
+        self
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    // NOTE: cached results came from the old templates, so they are dropped as well
+    pub fn set_recognizer(&mut self, recognizer: Recognizer<M, A>) {
+        self.recognizer = recognizer;
+        self.money_cache = None;
+        self.attack_cache = None;
+    }
+
+    pub fn stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    pub fn tick(&mut self) -> Result<Tick, CaptureError> {
+        let scale = geometry_scale(self.source.geometry());
+        if scale != self.recognizer.scale() {
+            self.recognizer.set_scale(scale);
+            self.money_cache = None;
+            self.attack_cache = None;
+        }
+
+        let regions = [MONEY_REGION.scaled(scale), ATTACK_REGION.scaled(scale)];
+        let frame = match self.source.capture(&regions) {
+            Ok(frame) => frame,
+            Err(e) => {
+                match e {
+                    CaptureError::Timeout => self.stats.skipped += 1,
+                    CaptureError::Lost(_) | CaptureError::Fatal(_) => self.stats.failed += 1,
+                    CaptureError::Exhausted => {}
+                }
+                return Err(e);
+            }
+        };
+
+        self.stats.captured += 1;
+        if let Some(expected) = self.next_sequence {
+            self.stats.dropped += frame.sequence.saturating_sub(expected);
+        }
+        self.next_sequence = Some(frame.sequence + 1);
+
+        let start = Instant::now();
+
+        let money = recognize_cached(
+            &mut self.money_cache,
+            &frame.regions[0],
+            self.change_tolerance,
+            &mut self.stats.unchanged,
+            |region| self.recognizer.recognize_money(region),
+        );
+        let attack = recognize_cached(
+            &mut self.attack_cache,
+            &frame.regions[1],
+            self.change_tolerance,
+            &mut self.stats.unchanged,
+            |region| self.recognizer.recognize_attack(region),
+        );
+
+        let duration = start.elapsed();
+
+        let anomaly = self.processor.inspect(&money, &attack);
+        let future = self.processor.process(&money, &attack);
+        let latency = frame.timestamp.elapsed();
+
+        Ok(Tick {
+            timestamp: frame.timestamp,
+            sequence: frame.sequence,
+            regions: frame.regions,
+            money,
+            attack,
+            future,
+            duration,
+            latency,
+            anomaly,
+        })
+    }
+}
@@ -1,3 +1,5 @@
+use crate::recognizer::Reading;
+
 const LEVEL_DATA: [(u16, u32); 30] = [
     (0, 400),
     (25, 500),
@@ -31,35 +33,68 @@ const LEVEL_DATA: [(u16, u32); 30] = [
     (1500, 0),
 ];
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Anomaly {
+    AttackDecreased { from: u16, to: u16 },
+    MoneyJumped { from: u32, to: u32 },
+    AttackConfidence(f32),
+}
+
+// NOTE: remembers the last confident readings to fall back on
+#[derive(Default)]
 pub struct Processor {
-    last_money: u32,
-    last_attack: u16,
+    last_money: Option<u32>,
+    last_attack: Option<u16>,
 }
 
 impl Processor {
-    fn process_money(&self, (money, _score): (u32, u32)) -> Option<u32> {
-        Some(money)
-    }
-
-    const ATTACK_PASSING_SCORE: u32 = 100;
-    fn process_attack(&self, (attack, score): (u16, u32)) -> Option<u16> {
-        if score <= Self::ATTACK_PASSING_SCORE{
-            Some(attack)
-        } else {
-            Some(0)
+    fn settle<T: Copy>(reading: &Reading<T>, last: Option<T>) -> Option<T> {
+        match *reading {
+            Reading::Confident { value, .. } => Some(value),
+            Reading::Uncertain { value, .. } => last.or(Some(value)),
+            Reading::Missing(_) => last,
         }
     }
 
     pub fn new() -> Self {
-        Self {
-            last_money: 0,
-            last_attack: 0,
+        Self::default()
+    }
+
+    // NOTE: money only jumps up by kill rewards, spending is unlimited
+    const MONEY_JUMP_LIMIT: u32 = 50000;
+
+    // NOTE: compares against the last confident readings, so call it before process
+    pub fn inspect(&self, money: &Reading<u32>, attack: &Reading<u16>) -> Option<Anomaly> {
+        if let Reading::Uncertain { .. } = attack {
+            Some(Anomaly::AttackConfidence(
+                attack.confidence().unwrap_or(0.0),
+            ))
+        } else if let (Some(last), Some(attack)) = (self.last_attack, attack.confident())
+            && attack < last
+        {
+            Some(Anomaly::AttackDecreased {
+                from: last,
+                to: attack,
+            })
+        } else if let (Some(last), Some(money)) = (self.last_money, money.confident())
+            && money > last.saturating_add(Self::MONEY_JUMP_LIMIT)
+        {
+            Some(Anomaly::MoneyJumped {
+                from: last,
+                to: money,
+            })
+        } else {
+            None
         }
     }
 
-    pub fn process(&self, money: (u32, u32), attack: (u16, u32)) -> (u32, u16) {
-        let money = self.process_money(money).unwrap();
-        let attack = self.process_attack(attack).unwrap();
+    // NOTE: None until both fields have been read at least once
+    pub fn process(&mut self, money: &Reading<u32>, attack: &Reading<u16>) -> Option<(u32, u16)> {
+        self.last_money = money.confident().or(self.last_money);
+        self.last_attack = attack.confident().or(self.last_attack);
+
+        let money = Self::settle(money, self.last_money)?;
+        let attack = Self::settle(attack, self.last_attack)?;
 
         let mut remaining_money = money;
         let mut final_attack = attack;
@@ -75,6 +110,6 @@ impl Processor {
             }
         }
 
-        (remaining_money, final_attack)
+        Some((remaining_money, final_attack))
     }
 }
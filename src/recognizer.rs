@@ -1,151 +1,16 @@
-use std::{collections::HashMap, sync::OnceLock};
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
 
-use image::{GenericImageView, GrayImage, Pixel, imageops};
+use image::{GenericImageView, GrayImage, Luma, Pixel, RgbaImage, imageops};
 
+use crate::binarizer::Binarizer;
+use crate::capturer::Region;
 use crate::constants::*;
-
-const COMMA_WIDTH: u8 = 3;
-
-fn otsu_threshold(gray: &GrayImage) -> u8 {
-    let mut hist = [0u32; 256];
-    let gray_data = gray.as_raw();
-    for &pixel in gray_data {
-        hist[pixel as usize] += 1;
-    }
-
-    let total_pixels = gray_data.len() as u32;
-
-    let mut cum_pixels = [0u32; 256];
-    let mut cum_sum = [0u64; 256];
-    cum_pixels[0] = hist[0];
-    cum_sum[0] = (0 * hist[0]) as u64;
-
-    for i in 1..256 {
-        cum_pixels[i] = cum_pixels[i - 1] + hist[i];
-        cum_sum[i] = cum_sum[i - 1] + (i as u64) * (hist[i] as u64);
-    }
-
-    let total_sum = cum_sum[255];
-
-    let mut best_threshold = 0;
-    let mut max_variance = 0.0;
-
-    for t in 0..256 {
-        let pixels_bg = cum_pixels[t];
-        let pixels_fg = total_pixels - pixels_bg;
-
-        if pixels_bg == 0 || pixels_fg == 0 {
-            continue;
-        }
-
-        let sum_bg = cum_sum[t];
-        let sum_fg = total_sum - sum_bg;
-
-        let mean_bg = sum_bg as f64 / pixels_bg as f64;
-        let mean_fg = sum_fg as f64 / pixels_fg as f64;
-
-        let variance = (pixels_bg as f64) * (pixels_fg as f64) * (mean_bg - mean_fg).powi(2);
-
-        if variance > max_variance {
-            max_variance = variance;
-            best_threshold = t;
-        }
-    }
-
-    best_threshold as u8
-}
-
-pub fn otsu_binarize<T>(img: &T) -> GrayImage
-where
-    T: GenericImageView,
-    T::Pixel: Pixel<Subpixel = u8>,
-{
-    let gray: GrayImage = imageops::grayscale(img);
-    let threshold = otsu_threshold(&gray);
-
-    let gray_data = gray.as_raw();
-    let binary_data: Vec<u8> = gray_data
-        .iter()
-        .map(|&pixel| if pixel > threshold { 255 } else { 0 })
-        .collect();
-
-    GrayImage::from_vec(img.width(), img.height(), binary_data)
-        .expect("Failed to create binary image")
-}
-
-// NOTE: left close right open
-pub fn vertical_digit_divide(binary: &GrayImage) -> Vec<(u8, u8)> {
-    let mut intervals: Vec<(u8, u8)> = Vec::new();
-    let (mut in_character, mut char_begin) = (false, 0u8);
-
-    let width = binary.width() as usize;
-    let height = binary.height() as usize;
-    let binary_data = binary.as_raw();
-
-    for x in 0..width {
-        let mut has_white = false;
-        for y in 0..height {
-            let idx = y * width + x;
-            if binary_data[idx] != 0 {
-                has_white = true;
-                break;
-            }
-        }
-
-        match (in_character, has_white) {
-            (false, true) => {
-                in_character = true;
-                char_begin = x as u8;
-            }
-            (true, false) => {
-                in_character = false;
-                if (x as u8 - char_begin) > COMMA_WIDTH {
-                    intervals.push((char_begin, x as u8));
-                }
-            }
-            _ => {}
-        }
-    }
-
-    if in_character {
-        let char_width = (width - 1) as u8;
-        if char_width > COMMA_WIDTH {
-            intervals.push((char_begin, char_width));
-        }
-    }
-
-    intervals
-}
-
-pub fn vertical_number_divide(binary: &GrayImage) -> (u8, u8) {
-    let mut intervals: (u8, u8) = (0, 0);
-
-    let width = binary.width() as usize;
-    let height = binary.height() as usize;
-    let binary_data = binary.as_raw();
-
-    'begin: for x in 0..width {
-        for y in 0..height {
-            let idx = y * width + x;
-            if binary_data[idx] != 0 {
-                intervals.0 = x as u8;
-                break 'begin;
-            }
-        }
-    }
-
-    'end: for x in (0..width).rev() {
-        for y in 0..height {
-            let idx = y * width + x;
-            if binary_data[idx] != 0 {
-                intervals.1 = (x + 1) as u8;
-                break 'end;
-            }
-        }
-    }
-
-    intervals
-}
+use crate::matcher::{DistanceMatcher, Matcher, Score};
+use crate::segmenter::Segmenter;
 
 pub fn bidirectional_distance_transform(binary: &GrayImage) -> (GrayImage, GrayImage) {
     let (width, height) = binary.dimensions();
@@ -214,14 +79,25 @@ pub fn bidirectional_distance_transform(binary: &GrayImage) -> (GrayImage, GrayI
     )
 }
 
-pub fn template_match(src_binary: &GrayImage, tmpl_fg: &GrayImage, tmpl_bg: &GrayImage) -> u32 {
+// NOTE: None when the crop and the template differ in size
+pub fn template_match(
+    src_binary: &GrayImage,
+    tmpl_fg: &GrayImage,
+    tmpl_bg: &GrayImage,
+) -> Option<u32> {
+    if src_binary.dimensions() != tmpl_fg.dimensions()
+        || tmpl_fg.dimensions() != tmpl_bg.dimensions()
+    {
+        return None;
+    }
+
     let src_binary_data = src_binary.as_raw();
     let tmpl_fg_data = tmpl_fg.as_raw();
     let tmpl_bg_data = tmpl_bg.as_raw();
 
     let mut score = 0u32;
 
-    for i in 0..src_binary_data.len().min(tmpl_fg_data.len()) {
+    for i in 0..src_binary_data.len() {
         score += if src_binary_data[i] == 255 {
             tmpl_fg_data[i] as u32
         } else {
@@ -233,143 +109,801 @@ pub fn template_match(src_binary: &GrayImage, tmpl_fg: &GrayImage, tmpl_bg: &Gra
         println!("{score}")
     }
 
-    score
+    Some(score)
 }
 
-// fg_dist bg_dist
-pub static MONEY_DIGIT_DISTANCES: OnceLock<HashMap<u8, (GrayImage, GrayImage)>> = OnceLock::new();
-pub static ATTACK_DISTANCES: OnceLock<HashMap<u16, (GrayImage, GrayImage)>> = OnceLock::new();
-pub static ATTACK_NUMBER_WIDTHS: OnceLock<HashMap<u16, u8>> = OnceLock::new();
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unrecognized {
+    // NOTE: nothing but background (or nothing at all) in the region
+    EmptyRegion,
+    NoDigits,
+    DigitCount(usize),
+    // NOTE: glyphs in an impossible order, e.g. no trailing % on the attack
+    Malformed,
+}
 
-pub fn initialize() {
-    let mut distances = HashMap::new();
-    for i in MONEY_DIGITS {
-        if let Ok(source) = image::open(format!("{MONEY_DIGIT_TEMPLATES_DIR}/{i}.png")) {
-            let binary = source.to_luma8();
-            distances.insert(i, bidirectional_distance_transform(&binary));
-        } else {
-            panic!("failed to load template {i}");
+impl fmt::Display for Unrecognized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unrecognized::EmptyRegion => write!(f, "区域为空"),
+            Unrecognized::NoDigits => write!(f, "无数字"),
+            Unrecognized::DigitCount(count) => write!(f, "{count}位数字"),
+            Unrecognized::Malformed => write!(f, "格式错误"),
         }
     }
-    MONEY_DIGIT_DISTANCES.set(distances).unwrap();
-
-    let mut distances = HashMap::new();
-    let mut widths = HashMap::new();
-    for i in ATTACK_LEVELS {
-        if let Ok(source) = image::open(format!("{ATTACK_LEVELS_TEMPLATES_DIR}/{i}.png")) {
-            let binary = source.to_luma8();
-            distances.insert(i, bidirectional_distance_transform(&binary));
-            if ATTACK_WIDTHS_LEVELS_NUM.contains(&i) {
-                let (begin, end) = vertical_number_divide(&binary);
-                widths.insert(i, end - begin);
+}
+
+// NOTE: confidence is 1 for a perfect match and falls with the mean template distance per pixel,
+// margin is how far the runner-up candidate is behind, offset is where the best match was found,
+// bounds is the glyph's box in pixels of the recognized crop
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlyphMatch {
+    pub glyph: char,
+    pub confidence: f32,
+    pub margin: f32,
+    pub offset: (i32, i32),
+    pub bounds: Region,
+}
+
+impl GlyphMatch {
+    pub fn passing(&self) -> bool {
+        self.confidence >= GLYPH_PASSING_CONFIDENCE && self.margin >= GLYPH_PASSING_MARGIN
+    }
+}
+
+pub fn match_confidence(score: u32, pixels: usize) -> f32 {
+    if pixels == 0 {
+        return 0.0;
+    }
+    1.0 / (1.0 + score as f32 / pixels as f32)
+}
+
+// NOTE: the best scoring candidate is kept
+pub fn best_glyph(
+    bounds: Region,
+    candidates: impl Iterator<Item = (char, Score)>,
+) -> Option<GlyphMatch> {
+    let (mut best, mut runner_up): (Option<(char, Score)>, f32) = (None, 0.0);
+    for (glyph, score) in candidates {
+        match best {
+            Some((_, best_score)) if score.confidence <= best_score.confidence => {
+                runner_up = runner_up.max(score.confidence);
+            }
+            _ => {
+                runner_up = best.map_or(runner_up, |(_, best_score)| best_score.confidence);
+                best = Some((glyph, score));
             }
+        }
+    }
+    best.map(|(glyph, score)| GlyphMatch {
+        glyph,
+        confidence: score.confidence,
+        margin: score.confidence - runner_up,
+        offset: score.offset,
+        bounds,
+    })
+}
+
+// NOTE: glyphs holds the per-glyph breakdown, left to right
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reading<T> {
+    Confident { value: T, glyphs: Vec<GlyphMatch> },
+    Uncertain { value: T, glyphs: Vec<GlyphMatch> },
+    Missing(Unrecognized),
+}
+
+impl<T: Copy> Reading<T> {
+    // NOTE: classifies by the weakest glyph
+    fn from_glyphs(value: T, glyphs: Vec<GlyphMatch>) -> Self {
+        if glyphs.iter().all(GlyphMatch::passing) {
+            Reading::Confident { value, glyphs }
         } else {
-            panic!("failed to load template {}", i);
+            Reading::Uncertain { value, glyphs }
         }
     }
-    ATTACK_DISTANCES.set(distances).unwrap();
-    ATTACK_NUMBER_WIDTHS.set(widths).unwrap();
-}
-
-//TODO: recognize failed will be 1, need fix
-pub fn recognize_money<T>(source: &T) -> (u32, u32)
-where
-    T: GenericImageView<Pixel: Pixel<Subpixel = u8>>,
-{
-    let binary = otsu_binarize(source);
-    let (_, height) = binary.dimensions();
-    let (mut money, mut score) = (0u32, 0u32);
-
-    for &(begin, end) in &vertical_digit_divide(&binary) {
-        let cropped = {
-            let single = imageops::crop_imm(&binary, begin as u32, 0, (end - begin) as u32, height)
-                .to_image();
-            if single.width()
-                == MONEY_DIGIT_DISTANCES
-                    .get()
-                    .unwrap()
-                    .get(&0)
-                    .unwrap()
-                    .0
-                    .width()
-            {
-                single
-            } else {
-                imageops::resize(
-                    &single,
-                    MONEY_DIGIT_DISTANCES
-                        .get()
-                        .unwrap()
-                        .get(&0)
-                        .unwrap()
-                        .0
-                        .width(),
-                    MONEY_DIGIT_DISTANCES
-                        .get()
-                        .unwrap()
-                        .get(&0)
-                        .unwrap()
-                        .0
-                        .height(),
-                    imageops::FilterType::Nearest,
-                )
+
+    fn rescale_bounds(&mut self, factor: f32) {
+        if let Reading::Confident { glyphs, .. } | Reading::Uncertain { glyphs, .. } = self {
+            for glyph in glyphs {
+                glyph.bounds = glyph.bounds.scaled(factor);
             }
-        };
+        }
+    }
+
+    pub fn value(&self) -> Option<T> {
+        match *self {
+            Reading::Confident { value, .. } | Reading::Uncertain { value, .. } => Some(value),
+            Reading::Missing(_) => None,
+        }
+    }
+
+    pub fn glyphs(&self) -> &[GlyphMatch] {
+        match self {
+            Reading::Confident { glyphs, .. } | Reading::Uncertain { glyphs, .. } => glyphs,
+            Reading::Missing(_) => &[],
+        }
+    }
+
+    // NOTE: the confidence of the weakest glyph
+    pub fn confidence(&self) -> Option<f32> {
+        self.glyphs()
+            .iter()
+            .map(|glyph| glyph.confidence)
+            .min_by(f32::total_cmp)
+    }
+
+    pub fn confident(&self) -> Option<T> {
+        match *self {
+            Reading::Confident { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+}
 
-        let result = MONEY_DIGITS
-            .map(|i| {
-                if cfg!(feature = "score_log") {
-                    print!("digit {i} score: ");
-                }
-                let (tmpl_fg, tmpl_bg) = MONEY_DIGIT_DISTANCES.get().unwrap().get(&i).unwrap();
-                (i, template_match(&cropped, tmpl_fg, tmpl_bg))
-            })
-            .min_by_key(|&(_, score)| score)
-            .unwrap();
-
-        if cfg!(feature = "score_log") {
-            println!("--------RESULT: {}---------", result.0);
+impl<T: fmt::Display + Copy> fmt::Display for Reading<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let confidence = self.confidence().unwrap_or(0.0);
+        match self {
+            Reading::Confident { value, .. } => write!(f, "{value}({confidence:.2})"),
+            Reading::Uncertain { value, .. } => write!(f, "{value}?({confidence:.2})"),
+            Reading::Missing(reason) => write!(f, "--({reason})"),
         }
-        money = money * 10 + result.0 as u32;
-        score += result.1;
     }
+}
 
-    (money, score)
+// NOTE: a region without contrast binarizes to a single color
+fn is_blank(binary: &GrayImage) -> bool {
+    let data = binary.as_raw();
+    data.first()
+        .is_none_or(|&first| data.iter().all(|&pixel| pixel == first))
 }
 
-pub fn recognize_attack<T>(source: &T) -> (u16, u32)
-where
-    T: GenericImageView<Pixel: Pixel<Subpixel = u8>>,
-{
-    let binary = otsu_binarize(source);
-    let (begin, end) = vertical_number_divide(&binary);
-    let width = end - begin;
+#[derive(Debug)]
+pub enum TemplateIssue {
+    Missing,
+    Unreadable(String),
+    WrongSize {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+}
 
-    let similar = ATTACK_WIDTHS_LEVELS_NUM
-        .iter()
-        .min_by_key(|&x| {
-            let w = ATTACK_NUMBER_WIDTHS.get().unwrap().get(x).unwrap();
-            std::cmp::max(*w, width) - std::cmp::min(*w, width)
-        })
-        .unwrap();
+#[derive(Debug)]
+pub struct TemplateProblem {
+    pub path: PathBuf,
+    pub issue: TemplateIssue,
+}
+
+impl fmt::Display for TemplateProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path.display();
+        match &self.issue {
+            TemplateIssue::Missing => write!(f, "{path}: 缺失"),
+            TemplateIssue::Unreadable(e) => write!(f, "{path}: 无法读取 {e}"),
+            TemplateIssue::WrongSize { expected, actual } => write!(
+                f,
+                "{path}: 尺寸应为{}x{}, 实际为{}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+        }
+    }
+}
+
+// NOTE: every problem found while loading, not just the first one
+#[derive(Debug)]
+pub struct TemplateError {
+    pub problems: Vec<TemplateProblem>,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "模板加载失败({}个问题):", self.problems.len())?;
+        for problem in &self.problems {
+            write!(f, "\n{problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+pub trait TemplateSource {
+    fn money_glyph_path(&self, glyph: char) -> PathBuf;
+
+    fn attack_glyph_path(&self, glyph: char) -> PathBuf;
+
+    fn load(&self, path: &Path) -> Result<GrayImage, TemplateIssue>;
+}
+
+// NOTE: file stem of a glyph template, % and , can not be used in every file name
+pub fn glyph_file_stem(glyph: char) -> String {
+    match glyph {
+        '%' => "percent".to_string(),
+        ',' => "comma".to_string(),
+        glyph => glyph.to_string(),
+    }
+}
+
+// NOTE: the layout written by the template generation binaries, {dir}/{glyph file stem}.png
+pub struct TemplateDir {
+    pub money: PathBuf,
+    pub attack: PathBuf,
+}
+
+impl Default for TemplateDir {
+    fn default() -> Self {
+        Self {
+            money: PathBuf::from(MONEY_DIGIT_TEMPLATES_DIR),
+            attack: PathBuf::from(ATTACK_GLYPH_TEMPLATES_DIR),
+        }
+    }
+}
+
+impl TemplateSource for TemplateDir {
+    fn money_glyph_path(&self, glyph: char) -> PathBuf {
+        self.money.join(format!("{}.png", glyph_file_stem(glyph)))
+    }
+
+    fn attack_glyph_path(&self, glyph: char) -> PathBuf {
+        self.attack.join(format!("{}.png", glyph_file_stem(glyph)))
+    }
+
+    fn load(&self, path: &Path) -> Result<GrayImage, TemplateIssue> {
+        if !path.is_file() {
+            return Err(TemplateIssue::Missing);
+        }
+        image::open(path)
+            .map(|image| image.to_luma8())
+            .map_err(|e| TemplateIssue::Unreadable(e.to_string()))
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryTemplates {
+    templates: HashMap<PathBuf, GrayImage>,
+}
+
+impl MemoryTemplates {
+    pub fn insert_money_glyph(&mut self, glyph: char, template: GrayImage) {
+        self.templates
+            .insert(self.money_glyph_path(glyph), template);
+    }
+
+    pub fn insert_attack_glyph(&mut self, glyph: char, template: GrayImage) {
+        self.templates
+            .insert(self.attack_glyph_path(glyph), template);
+    }
+}
+
+impl TemplateSource for MemoryTemplates {
+    fn money_glyph_path(&self, glyph: char) -> PathBuf {
+        PathBuf::from(format!("money/{}.png", glyph_file_stem(glyph)))
+    }
 
-    let candidate: Vec<u16> = match *similar {
-        ATTACK_WIDTHS_LEVEL_1 => ATTACK_WIDTHS_LEVELS_1.to_vec(),
-        ATTACK_WIDTHS_LEVEL_2 => ATTACK_WIDTHS_LEVELS_2.to_vec(),
-        ATTACK_WIDTHS_LEVEL_3 => ATTACK_WIDTHS_LEVELS_3.to_vec(),
-        _ => Vec::new(),
+    fn attack_glyph_path(&self, glyph: char) -> PathBuf {
+        PathBuf::from(format!("attack/{}.png", glyph_file_stem(glyph)))
+    }
+
+    fn load(&self, path: &Path) -> Result<GrayImage, TemplateIssue> {
+        self.templates
+            .get(path)
+            .cloned()
+            .ok_or(TemplateIssue::Missing)
+    }
+}
+
+fn load_template(
+    templates: &impl TemplateSource,
+    path: PathBuf,
+    expected: impl FnOnce(&GrayImage) -> (u32, u32),
+    problems: &mut Vec<TemplateProblem>,
+) -> Option<GrayImage> {
+    let issue = match templates.load(&path) {
+        Ok(template) => {
+            let expected = expected(&template);
+            if template.dimensions() == expected {
+                return Some(template);
+            }
+            TemplateIssue::WrongSize {
+                expected,
+                actual: template.dimensions(),
+            }
+        }
+        Err(issue) => issue,
     };
+    problems.push(TemplateProblem { path, issue });
+    None
+}
 
-    candidate
-        .iter()
-        .map(|&atk| {
+struct Prepared<T> {
+    dimensions: (u32, u32),
+    template: T,
+}
+
+// NOTE: glyphs are cropped tightly, so they differ in width but share one height
+fn load_glyphs<M: Matcher>(
+    templates: &impl TemplateSource,
+    matcher: &M,
+    glyphs: impl Iterator<Item = (char, PathBuf)>,
+    problems: &mut Vec<TemplateProblem>,
+) -> Vec<(char, Prepared<M::Template>)> {
+    let mut prepared = Vec::new();
+    let mut glyph_height = None;
+    for (glyph, path) in glyphs {
+        if let Some(binary) = load_template(
+            templates,
+            path,
+            |template| (template.width(), glyph_height.unwrap_or(template.height())),
+            problems,
+        ) {
+            glyph_height.get_or_insert(binary.height());
+            prepared.push((
+                glyph,
+                Prepared {
+                    dimensions: binary.dimensions(),
+                    template: matcher.prepare(&binary),
+                },
+            ));
+        }
+    }
+    prepared
+}
+
+// NOTE: scaled to fit within the template's size keeping its aspect ratio, and centered on fill
+fn fit(
+    segment: &GrayImage,
+    (width, height): (u32, u32),
+    filter: imageops::FilterType,
+    fill: u8,
+) -> GrayImage {
+    let factor =
+        (width as f32 / segment.width() as f32).min(height as f32 / segment.height() as f32);
+    let fitted_width = ((segment.width() as f32 * factor).round() as u32).clamp(1, width);
+    let fitted_height = ((segment.height() as f32 * factor).round() as u32).clamp(1, height);
+    let resized;
+    let fitted = if (fitted_width, fitted_height) == segment.dimensions() {
+        segment
+    } else {
+        resized = imageops::resize(segment, fitted_width, fitted_height, filter);
+        &resized
+    };
+
+    let mut canvas = GrayImage::from_pixel(width, height, Luma([fill]));
+    imageops::replace(
+        &mut canvas,
+        fitted,
+        ((width - fitted_width) / 2) as i64,
+        ((height - fitted_height) / 2) as i64,
+    );
+    canvas
+}
+
+// NOTE: the segment is compared with every template at that template's own size,
+// so a narrow 1 is padded rather than stretched into something 7 wide
+fn classify<M: Matcher>(
+    matcher: &M,
+    binary: &GrayImage,
+    input: &GrayImage,
+    bounds: Region,
+    templates: &[(char, Prepared<M::Template>)],
+) -> Option<GlyphMatch> {
+    let segment =
+        &imageops::crop_imm(input, bounds.x, bounds.y, bounds.width, bounds.height).to_image();
+    let (filter, fill) = if M::BINARY {
+        (imageops::FilterType::Nearest, 0)
+    } else {
+        // NOTE: grayscale crops are padded with the mean of their background pixels
+        let mask = imageops::crop_imm(binary, bounds.x, bounds.y, bounds.width, bounds.height);
+        let background: Vec<u32> = mask
+            .pixels()
+            .zip(segment.pixels())
+            .filter(|((_, _, mask), _)| mask.0[0] == 0)
+            .map(|(_, pixel)| pixel.0[0] as u32)
+            .collect();
+        let fill = background.iter().sum::<u32>() / (background.len() as u32).max(1);
+        (imageops::FilterType::Triangle, fill as u8)
+    };
+    best_glyph(
+        bounds,
+        templates.iter().map(|(glyph, prepared)| {
+            let fitted = fit(segment, prepared.dimensions, filter, fill);
+            let score = matcher.score(&fitted, &prepared.template);
             if cfg!(feature = "score_log") {
-                print!("attack {atk} score: ");
+                println!("glyph {glyph} score: {score:?}");
             }
+            (*glyph, score)
+        }),
+    )
+}
+
+// NOTE: how far from each template width a cut between touching glyphs is searched for
+const SPLIT_SEARCH: u32 = 2;
+
+// NOTE: an over-wide segment is cut where a template width puts a glyph boundary,
+// right at it and at the emptiest projection column nearby. every hypothesis is scored by its weakest glyph
+// and the segment is only split when that beats reading it whole. parts are shared between
+// hypotheses, so each one's reading is kept in known
+fn read_segment<M: Matcher>(
+    matcher: &M,
+    binary: &GrayImage,
+    input: &GrayImage,
+    bounds: Region,
+    templates: &[(char, Prepared<M::Template>)],
+    known: &mut HashMap<Region, Vec<GlyphMatch>>,
+) -> Vec<GlyphMatch> {
+    if let Some(glyphs) = known.get(&bounds) {
+        return glyphs.clone();
+    }
+    let glyphs = split_segment(matcher, binary, input, bounds, templates, known);
+    known.insert(bounds, glyphs.clone());
+    glyphs
+}
+
+fn split_segment<M: Matcher>(
+    matcher: &M,
+    binary: &GrayImage,
+    input: &GrayImage,
+    bounds: Region,
+    templates: &[(char, Prepared<M::Template>)],
+    known: &mut HashMap<Region, Vec<GlyphMatch>>,
+) -> Vec<GlyphMatch> {
+    let whole = vec![classify(matcher, binary, input, bounds, templates).unwrap()];
+    let widths = templates.iter().map(|(_, prepared)| prepared.dimensions.0);
+    let (Some(narrowest), Some(widest)) = (widths.clone().min(), widths.clone().max()) else {
+        return whole;
+    };
+    if bounds.width as f32 <= widest as f32 * TOUCHING_WIDTH_RATIO {
+        return whole;
+    }
+
+    let profile: Vec<u32> = (bounds.x..bounds.x + bounds.width)
+        .map(|x| {
+            (bounds.y..bounds.y + bounds.height)
+                .filter(|&y| binary.get_pixel(x, y).0[0] != 0)
+                .count() as u32
+        })
+        .collect();
+
+    // NOTE: both parts stay at least half as wide as the narrowest glyph
+    let usable = (narrowest / 2).max(1)..bounds.width.saturating_sub(narrowest / 2);
+    let mut cuts: Vec<u32> = widths
+        .flat_map(|width| {
+            let emptiest = (0..=SPLIT_SEARCH)
+                .flat_map(|d| [width.checked_sub(d), width.checked_add(d)])
+                .flatten()
+                .filter(|cut| usable.contains(cut))
+                .min_by_key(|&cut| profile[cut as usize]);
+            [emptiest, Some(width).filter(|cut| usable.contains(cut))]
+        })
+        .flatten()
+        .collect();
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let weakest = |glyphs: &[GlyphMatch]| {
+        glyphs
+            .iter()
+            .map(|glyph| glyph.confidence)
+            .fold(f32::MAX, f32::min)
+    };
+    let mut best = whole;
+    for cut in cuts {
+        let left = Region::new(bounds.x, bounds.y, cut, bounds.height);
+        let right = Region::new(bounds.x + cut, bounds.y, bounds.width - cut, bounds.height);
+        let mut glyphs = read_segment(matcher, binary, input, left, templates, known);
+        glyphs.extend(read_segment(
+            matcher, binary, input, right, templates, known,
+        ));
+        if weakest(&glyphs) > weakest(&best) {
+            best = glyphs;
+        }
+    }
+    best
+}
+
+// NOTE: segments on the binarized crop, and matches either that or the grayscale one
+fn read_glyphs<M: Matcher>(
+    crop: &RgbaImage,
+    binarizer: Binarizer,
+    segmenter: Segmenter,
+    matcher: &M,
+    templates: &[(char, Prepared<M::Template>)],
+    max_glyphs: usize,
+) -> Result<Vec<GlyphMatch>, Unrecognized> {
+    let gray = imageops::grayscale(crop);
+    let binary = binarizer.binarize(crop);
+    if is_blank(&binary) {
+        return Err(Unrecognized::EmptyRegion);
+    }
 
-            let (tmpl_fg, tmpl_bg) = ATTACK_DISTANCES.get().unwrap().get(&atk).unwrap();
-            (atk, template_match(&binary, tmpl_fg, tmpl_bg))
+    // NOTE: segments narrower than half of any other glyph are specks, unless there is a
+    // separator template and they pass as a separator
+    let narrowest = templates
+        .iter()
+        .filter(|(glyph, _)| *glyph != MONEY_SEPARATOR)
+        .map(|(_, prepared)| prepared.dimensions.0)
+        .min()
+        .unwrap_or(0);
+    let narrow = |segment: &Region| segment.width * 2 < narrowest;
+    let separators = templates.iter().any(|(glyph, _)| *glyph == MONEY_SEPARATOR);
+    let segments: Vec<Region> = segmenter
+        .segment(&binary)
+        .into_iter()
+        .filter(|segment| separators || !narrow(segment))
+        .collect();
+    match segments.iter().filter(|segment| !narrow(segment)).count() {
+        0 => return Err(Unrecognized::NoDigits),
+        count if count > max_glyphs => return Err(Unrecognized::DigitCount(count)),
+        _ => {}
+    }
+
+    let input = if M::BINARY { &binary } else { &gray };
+    let mut known = HashMap::new();
+    let glyphs: Vec<GlyphMatch> = segments
+        .into_iter()
+        .flat_map(|bounds| {
+            let glyphs = read_segment(matcher, &binary, input, bounds, templates, &mut known);
+            let speck = narrow(&bounds)
+                && !glyphs
+                    .iter()
+                    .all(|glyph| glyph.glyph == MONEY_SEPARATOR && glyph.passing());
+            if speck { Vec::new() } else { glyphs }
+        })
+        .collect();
+    if cfg!(feature = "score_log") {
+        for glyph in &glyphs {
+            println!("--------RESULT: {}---------", glyph.glyph);
+        }
+    }
+
+    match glyphs.len() {
+        count if count > max_glyphs => Err(Unrecognized::DigitCount(count)),
+        _ => Ok(glyphs),
+    }
+}
+
+// NOTE: the matcher is chosen per field
+pub struct Recognizer<M: Matcher = DistanceMatcher, A: Matcher = DistanceMatcher> {
+    money_matcher: M,
+    attack_matcher: A,
+    money_glyphs: Vec<(char, Prepared<M::Template>)>,
+    attack_glyphs: Vec<(char, Prepared<A::Template>)>,
+    money_binarizer: Binarizer,
+    attack_binarizer: Binarizer,
+    money_segmenter: Segmenter,
+    attack_segmenter: Segmenter,
+
+    // NOTE: size of the crops relative to REFERENCE_GEOMETRY, and the factors tried around it
+    scale: f32,
+    scales: Vec<f32>,
+}
+
+impl Recognizer {
+    pub fn new(templates: &impl TemplateSource) -> Result<Self, TemplateError> {
+        Self::with_matchers(
+            templates,
+            DistanceMatcher::default(),
+            DistanceMatcher::default(),
+        )
+    }
+}
+
+impl<M: Matcher, A: Matcher> Recognizer<M, A> {
+    pub fn with_matchers(
+        templates: &impl TemplateSource,
+        money_matcher: M,
+        attack_matcher: A,
+    ) -> Result<Self, TemplateError> {
+        let mut problems = Vec::new();
+
+        // NOTE: template packs made before separators were read have no comma template,
+        // their money is read without it as before
+        let separator = templates.money_glyph_path(MONEY_SEPARATOR);
+        let separators = !matches!(templates.load(&separator), Err(TemplateIssue::Missing));
+        let money_glyphs = load_glyphs(
+            templates,
+            &money_matcher,
+            MONEY_GLYPHS
+                .iter()
+                .filter(|&&glyph| separators || glyph != MONEY_SEPARATOR)
+                .map(|&glyph| (glyph, templates.money_glyph_path(glyph))),
+            &mut problems,
+        );
+        let attack_glyphs = load_glyphs(
+            templates,
+            &attack_matcher,
+            ATTACK_GLYPHS
+                .iter()
+                .map(|&glyph| (glyph, templates.attack_glyph_path(glyph))),
+            &mut problems,
+        );
+
+        if !problems.is_empty() {
+            return Err(TemplateError { problems });
+        }
+
+        Ok(Self {
+            money_matcher,
+            attack_matcher,
+            money_glyphs,
+            attack_glyphs,
+            money_binarizer: MONEY_BINARIZER,
+            attack_binarizer: ATTACK_BINARIZER,
+            money_segmenter: MONEY_SEGMENTER,
+            attack_segmenter: ATTACK_SEGMENTER,
+            scale: 1.0,
+            scales: MATCH_SCALES.to_vec(),
         })
-        .min_by_key(|&(_, score)| score)
-        .unwrap()
+    }
+
+    // NOTE: the templates have to be generated with the same binarizers
+    pub fn with_binarizers(mut self, money: Binarizer, attack: Binarizer) -> Self {
+        self.money_binarizer = money;
+        self.attack_binarizer = attack;
+        self
+    }
+
+    pub fn with_segmenters(mut self, money: Segmenter, attack: Segmenter) -> Self {
+        self.money_segmenter = money;
+        self.attack_segmenter = attack;
+        self
+    }
+
+    // NOTE: factors that are not finite and positive have no crop size to resize to, dropped
+    pub fn with_scales(mut self, scales: Vec<f32>) -> Self {
+        self.scales = scales
+            .into_iter()
+            .filter(|factor| factor.is_finite() && *factor > 0.0)
+            .collect();
+        self
+    }
+
+    // NOTE: whether money is read with thousands separators, see MONEY_SEPARATOR
+    pub fn separators(&self) -> bool {
+        self.money_glyphs
+            .iter()
+            .any(|(glyph, _)| *glyph == MONEY_SEPARATOR)
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    // NOTE: the crop is brought back to template size at each factor, nearest to 1 first,
+    // the first confident reading wins, otherwise the most confident one, nearest on ties
+    fn best_scale<T: Copy>(
+        &self,
+        source: &impl GenericImageView<Pixel: Pixel<Subpixel = u8>>,
+        read: impl Fn(&RgbaImage) -> Reading<T>,
+    ) -> Reading<T> {
+        let crop = RgbaImage::from_fn(source.width(), source.height(), |x, y| {
+            source.get_pixel(x, y).to_rgba()
+        });
+        let mut factors = self.scales.clone();
+        factors.sort_by(|a, b| a.ln().abs().total_cmp(&b.ln().abs()));
+
+        let mut best = None;
+        for factor in factors {
+            let scale = self.scale * factor;
+            if !(scale.is_finite() && scale > 0.0) {
+                continue;
+            }
+            let reading = if scale == 1.0 {
+                read(&crop)
+            } else {
+                let width = ((crop.width() as f32 / scale).round() as u32).max(1);
+                let height = ((crop.height() as f32 / scale).round() as u32).max(1);
+                let mut reading = read(&imageops::resize(
+                    &crop,
+                    width,
+                    height,
+                    imageops::FilterType::Triangle,
+                ));
+                reading.rescale_bounds(scale);
+                reading
+            };
+            if matches!(reading, Reading::Confident { .. }) {
+                return reading;
+            }
+            // NOTE: a reading with a value beats none, then the higher confidence wins
+            let rank = |reading: &Reading<T>| (reading.value().is_some(), reading.confidence());
+            if best.as_ref().is_none_or(|best| rank(&reading) > rank(best)) {
+                best = Some(reading);
+            }
+        }
+        best.unwrap_or_else(|| read(&crop))
+    }
+
+    pub fn recognize_money<T>(&self, source: &T) -> Reading<u32>
+    where
+        T: GenericImageView<Pixel: Pixel<Subpixel = u8>>,
+    {
+        self.best_scale(source, |crop| self.read_money(crop))
+    }
+
+    pub fn recognize_attack<T>(&self, source: &T) -> Reading<u16>
+    where
+        T: GenericImageView<Pixel: Pixel<Subpixel = u8>>,
+    {
+        self.best_scale(source, |crop| self.read_attack(crop))
+    }
+
+    fn read_money(&self, crop: &RgbaImage) -> Reading<u32> {
+        let glyphs = match read_glyphs(
+            crop,
+            self.money_binarizer,
+            self.money_segmenter,
+            &self.money_matcher,
+            &self.money_glyphs,
+            MONEY_MAX_GLYPHS,
+        ) {
+            Ok(glyphs) => glyphs,
+            Err(reason) => return Reading::Missing(reason),
+        };
+
+        // NOTE: the HUD always groups, so the digits have to come in groups of three after
+        // the first. anything else, 1,23,4 as well as a dropped or misread separator in 12345,
+        // is a segmentation error. without a separator template the grouping can not be seen
+        let groups: Vec<&[GlyphMatch]> = glyphs
+            .split(|glyph| glyph.glyph == MONEY_SEPARATOR)
+            .collect();
+        let (first, rest) = groups.split_first().unwrap();
+        let grouped = (1..=3).contains(&first.len()) && rest.iter().all(|group| group.len() == 3);
+        if self.separators() && !grouped {
+            return Reading::Missing(Unrecognized::Malformed);
+        }
+
+        let digits = glyphs.len() + 1 - groups.len();
+        if digits > MONEY_MAX_DIGITS {
+            return Reading::Missing(Unrecognized::DigitCount(digits));
+        }
+        let money = groups
+            .iter()
+            .flat_map(|group| group.iter())
+            .fold(0u32, |money, digit| {
+                money * 10 + digit.glyph.to_digit(10).unwrap()
+            });
+        Reading::from_glyphs(money, glyphs)
+    }
+
+    fn read_attack(&self, crop: &RgbaImage) -> Reading<u16> {
+        let glyphs = match read_glyphs(
+            crop,
+            self.attack_binarizer,
+            self.attack_segmenter,
+            &self.attack_matcher,
+            &self.attack_glyphs,
+            ATTACK_MAX_GLYPHS,
+        ) {
+            Ok(glyphs) => glyphs,
+            Err(reason) => return Reading::Missing(reason),
+        };
+
+        // NOTE: digits followed by a single trailing %
+        let Some((percent, digits)) = glyphs.split_last() else {
+            return Reading::Missing(Unrecognized::NoDigits);
+        };
+        if percent.glyph != '%' || digits.is_empty() {
+            return Reading::Missing(Unrecognized::Malformed);
+        }
+        let Some(attack) = digits.iter().try_fold(0u16, |attack, digit| {
+            let digit = digit.glyph.to_digit(10)? as u16;
+            attack.checked_mul(10)?.checked_add(digit)
+        }) else {
+            return Reading::Missing(Unrecognized::Malformed);
+        };
+
+        // NOTE: a level the game does not have is still reported, but never trusted
+        if !ATTACK_LEVELS.contains(&attack) {
+            return Reading::Uncertain {
+                value: attack,
+                glyphs,
+            };
+        }
+        Reading::from_glyphs(attack, glyphs)
+    }
 }
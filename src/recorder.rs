@@ -0,0 +1,100 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use crate::pipeline::Tick;
+use crate::recognizer::Reading;
+
+pub const INDEX_FILE: &str = "index.csv";
+pub const REGION_DIRS: [&str; 2] = ["money", "attack"];
+
+// NOTE: value,confidence,state,glyphs where glyphs is glyph:confidence:margin:x:y:width:height
+// joined by ';' and quoted since a glyph may be ',', the box being in pixels of the saved region png
+fn reading_columns<T: fmt::Display + Copy>(reading: &Reading<T>) -> String {
+    let glyphs = reading
+        .glyphs()
+        .iter()
+        .map(|g| {
+            let b = g.bounds;
+            format!(
+                "{}:{:.3}:{:.3}:{}:{}:{}:{}",
+                g.glyph, g.confidence, g.margin, b.x, b.y, b.width, b.height
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(";");
+    let confidence = reading.confidence().unwrap_or(0.0);
+    match reading {
+        Reading::Confident { value, .. } => {
+            format!("{value},{confidence:.3},confident,\"{glyphs}\"")
+        }
+        Reading::Uncertain { value, .. } => {
+            format!("{value},{confidence:.3},uncertain,\"{glyphs}\"")
+        }
+        Reading::Missing(reason) => format!(",,{reason:?},"),
+    }
+}
+
+// NOTE: a session is a directory holding index.csv plus one png per region and tick:
+// money/{sequence}.png attack/{sequence}.png
+pub struct Recorder {
+    dir: PathBuf,
+    index: BufWriter<File>,
+    epoch: Option<Instant>,
+}
+
+impl Recorder {
+    pub fn create(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        for region in REGION_DIRS {
+            std::fs::create_dir_all(dir.join(region))?;
+        }
+
+        let mut index = BufWriter::new(File::create(dir.join(INDEX_FILE))?);
+        writeln!(
+            index,
+            "sequence,time_ms,money,money_confidence,money_state,money_glyphs,attack,attack_confidence,attack_state,attack_glyphs,future_money,future_attack,duration_us"
+        )?;
+
+        Ok(Self {
+            dir,
+            index,
+            epoch: None,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn record(&mut self, tick: &Tick) -> io::Result<()> {
+        for (region, image) in REGION_DIRS.iter().zip(&tick.regions) {
+            let path = self
+                .dir
+                .join(region)
+                .join(format!("{:08}.png", tick.sequence));
+            image.save(path).map_err(io::Error::other)?;
+        }
+
+        let epoch = *self.epoch.get_or_insert(tick.timestamp);
+        let future = match tick.future {
+            Some((money, attack)) => format!("{money},{attack}"),
+            None => ",".to_string(),
+        };
+        writeln!(
+            self.index,
+            "{},{},{},{},{},{}",
+            tick.sequence,
+            tick.timestamp.saturating_duration_since(epoch).as_millis(),
+            reading_columns(&tick.money),
+            reading_columns(&tick.attack),
+            future,
+            tick.duration.as_micros()
+        )?;
+        self.index.flush()
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+
+use image::GrayImage;
+
+use crate::capturer::Region;
+
+// NOTE: left close right open, narrow runs such as separators are kept
+pub fn vertical_digit_divide(binary: &GrayImage) -> Vec<(u32, u32)> {
+    let mut intervals: Vec<(u32, u32)> = Vec::new();
+    let (mut in_character, mut char_begin) = (false, 0u32);
+
+    let (width, height) = binary.dimensions();
+    let binary_data = binary.as_raw();
+
+    for x in 0..width {
+        let has_white = (0..height).any(|y| binary_data[(y * width + x) as usize] != 0);
+
+        match (in_character, has_white) {
+            (false, true) => {
+                in_character = true;
+                char_begin = x;
+            }
+            (true, false) => {
+                in_character = false;
+                intervals.push((char_begin, x));
+            }
+            _ => {}
+        }
+    }
+
+    if in_character {
+        intervals.push((char_begin, width));
+    }
+
+    intervals
+}
+
+// NOTE: bounding box, right and bottom exclusive
+#[derive(Clone, Copy, Debug)]
+struct Component {
+    left: u32,
+    top: u32,
+    right: u32,
+    bottom: u32,
+    area: u32,
+}
+
+impl Component {
+    fn union(self, other: Self) -> Self {
+        Self {
+            left: self.left.min(other.left),
+            top: self.top.min(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+            area: self.area + other.area,
+        }
+    }
+}
+
+// NOTE: 8-connected runs of ink
+fn components(binary: &GrayImage) -> Vec<Component> {
+    let (width, height) = binary.dimensions();
+    let ink = |x: u32, y: u32| binary.as_raw()[(y * width + x) as usize] != 0;
+    let mut visited = vec![false; (width * height) as usize];
+    let mut found = Vec::new();
+
+    for start_y in 0..height {
+        for start_x in 0..width {
+            if visited[(start_y * width + start_x) as usize] || !ink(start_x, start_y) {
+                continue;
+            }
+
+            visited[(start_y * width + start_x) as usize] = true;
+            let mut component = Component {
+                left: start_x,
+                top: start_y,
+                right: start_x + 1,
+                bottom: start_y + 1,
+                area: 0,
+            };
+            let mut queue = VecDeque::from([(start_x, start_y)]);
+            while let Some((x, y)) = queue.pop_front() {
+                component = component.union(Component {
+                    left: x,
+                    top: y,
+                    right: x + 1,
+                    bottom: y + 1,
+                    area: 1,
+                });
+                for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                    for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                        let idx = (ny * width + nx) as usize;
+                        if !visited[idx] && ink(nx, ny) {
+                            visited[idx] = true;
+                            queue.push_back((nx, ny));
+                        }
+                    }
+                }
+            }
+            found.push(component);
+        }
+    }
+
+    found
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Segmenter {
+    // NOTE: runs of columns with any ink, boxes span the full height
+    Projection,
+    // NOTE: connected components with tight boxes, parts overlapping in x (the % sign) are merged.
+    // min_area drops specks before merging, the rest drops whole glyphs afterwards,
+    // aspect is width / height. with separators, glyphs too small to pass that rest in the
+    // lower half of the rows and are kept as thousands separators, boxed over the same rows as
+    // the other glyphs like their template
+    Components {
+        min_area: u32,
+        min_width: u32,
+        min_height: u32,
+        max_aspect: f32,
+        separators: bool,
+    },
+}
+
+impl Segmenter {
+    // NOTE: boxes left to right, in pixels of the binary image
+    pub fn segment(&self, binary: &GrayImage) -> Vec<Region> {
+        match *self {
+            Segmenter::Projection => vertical_digit_divide(binary)
+                .into_iter()
+                .map(|(begin, end)| Region::new(begin, 0, end - begin, binary.height()))
+                .collect(),
+            Segmenter::Components {
+                min_area,
+                min_width,
+                min_height,
+                max_aspect,
+                separators,
+            } => {
+                let mut parts: Vec<Component> = components(binary)
+                    .into_iter()
+                    .filter(|part| part.area >= min_area)
+                    .collect();
+                parts.sort_by_key(|part| part.left);
+
+                let mut glyphs: Vec<Component> = Vec::new();
+                for part in parts {
+                    match glyphs.last_mut() {
+                        Some(glyph) if part.left < glyph.right => *glyph = glyph.union(part),
+                        _ => glyphs.push(part),
+                    }
+                }
+
+                let (kept, small): (Vec<Component>, Vec<Component>) =
+                    glyphs.into_iter().partition(|glyph| {
+                        let (width, height) = (glyph.right - glyph.left, glyph.bottom - glyph.top);
+                        width >= min_width
+                            && height >= min_height
+                            && width as f32 <= height as f32 * max_aspect
+                    });
+                let mut boxes: Vec<Region> = kept
+                    .iter()
+                    .map(|glyph| {
+                        Region::new(
+                            glyph.left,
+                            glyph.top,
+                            glyph.right - glyph.left,
+                            glyph.bottom - glyph.top,
+                        )
+                    })
+                    .collect();
+
+                let top = kept.iter().map(|glyph| glyph.top).min();
+                let bottom = kept.iter().map(|glyph| glyph.bottom).max();
+                if let (true, Some(top), Some(bottom)) = (separators, top, bottom) {
+                    let middle = top + (bottom - top) / 2;
+                    boxes.extend(
+                        small
+                            .iter()
+                            .filter(|part| part.top >= middle && part.top < bottom)
+                            .map(|part| {
+                                Region::new(part.left, top, part.right - part.left, bottom - top)
+                            }),
+                    );
+                    boxes.sort_by_key(|glyph| glyph.x);
+                }
+                boxes
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::{fs::File, io::BufWriter, path::Path, time::Duration};
+
+use common::temp_dir;
+use image::{
+    Delay, Frame, Rgba, RgbaImage,
+    codecs::gif::{GifEncoder, Repeat},
+};
+use nzm_visual::capturer::{CaptureError, FrameSource, Region, animation::Animation};
+
+const COLORS: [[u8; 4]; 3] = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+
+const DELAYS_MS: [u16; 3] = [100, 250, 40];
+
+const REGIONS: [Region; 2] = [Region::new(0, 0, 4, 4), Region::new(12, 4, 4, 4)];
+
+// NOTE: the left half of each frame has its color, the right half stays white
+fn frame(color: [u8; 4]) -> RgbaImage {
+    RgbaImage::from_fn(16, 8, |x, _| {
+        Rgba(if x < 8 { color } else { [255, 255, 255, 255] })
+    })
+}
+
+fn write_gif(path: &Path) {
+    let mut encoder = GifEncoder::new(File::create(path).unwrap());
+    encoder.set_repeat(Repeat::Infinite).unwrap();
+    encoder
+        .encode_frames(COLORS.iter().zip(DELAYS_MS).map(|(&color, delay)| {
+            Frame::from_parts(
+                frame(color),
+                0,
+                0,
+                Delay::from_numer_denom_ms(delay as u32, 1),
+            )
+        }))
+        .unwrap();
+}
+
+// NOTE: the default image is the first animation frame
+fn write_apng(path: &Path) {
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path).unwrap()), 16, 8);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(COLORS.len() as u32, 0).unwrap();
+    let mut writer = encoder.write_header().unwrap();
+    for (&color, delay) in COLORS.iter().zip(DELAYS_MS) {
+        writer.set_frame_delay(delay, 1000).unwrap();
+        writer.write_image_data(frame(color).as_raw()).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+// NOTE: checks the region pixels, sequences and timestamp gaps of every frame
+fn assert_plays_fixture(path: &Path) {
+    let mut animation = Animation::open(path).unwrap();
+    assert_eq!(animation.remaining(), 3);
+    assert_eq!(animation.geometry(), (16, 8));
+
+    let mut frames = Vec::new();
+    while let Ok(frame) = animation.capture(&REGIONS) {
+        frames.push(frame);
+    }
+    assert!(matches!(
+        animation.capture(&REGIONS),
+        Err(CaptureError::Exhausted)
+    ));
+
+    assert_eq!(frames.len(), 3);
+    for (i, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.sequence, i as u64);
+        assert_eq!(frame.regions[0].dimensions(), (4, 4));
+        assert_eq!(frame.regions[0].get_pixel(0, 0).0, COLORS[i]);
+        assert_eq!(frame.regions[1].get_pixel(0, 0).0, [255, 255, 255, 255]);
+    }
+    for (pair, delay) in frames.windows(2).zip(DELAYS_MS) {
+        let gap = pair[1].timestamp - pair[0].timestamp;
+        assert_eq!(gap, Duration::from_millis(delay as u64));
+    }
+}
+
+#[test]
+fn plays_gif_frames_with_their_delays() {
+    let dir = temp_dir("animation-gif");
+    let path = dir.join("fixture.gif");
+    write_gif(&path);
+    assert_plays_fixture(&path);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn plays_apng_frames_with_their_delays() {
+    let dir = temp_dir("animation-apng");
+    let path = dir.join("fixture.png");
+    write_apng(&path);
+    assert_plays_fixture(&path);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn plays_a_still_png_as_one_frame() {
+    let dir = temp_dir("animation-png");
+    let path = dir.join("still.png");
+    frame(COLORS[1]).save(&path).unwrap();
+
+    let mut animation = Animation::open(&path).unwrap();
+    assert_eq!(animation.remaining(), 1);
+    let frame = animation.capture(&REGIONS).unwrap();
+    assert_eq!(frame.sequence, 0);
+    assert_eq!(frame.regions[0].get_pixel(0, 0).0, COLORS[1]);
+    assert!(matches!(
+        animation.capture(&REGIONS),
+        Err(CaptureError::Exhausted)
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,142 @@
+use image::{GrayImage, Luma, Rgb, RgbImage, imageops};
+use nzm_visual::binarizer::{Binarizer, ColorSpace};
+
+const SIZE: (u32, u32) = (64, 12);
+const BOUNDARY: u32 = 32;
+
+fn is_stroke(x: u32) -> bool {
+    x % 6 < 2
+}
+
+// NOTE: two pixel strokes every six pixels, the left half in shadow and the right half lit
+fn uneven_strip() -> GrayImage {
+    GrayImage::from_fn(SIZE.0, SIZE.1, |x, _| {
+        let (background, ink) = if x < BOUNDARY { (20, 90) } else { (160, 230) };
+        Luma([if is_stroke(x) { ink } else { background }])
+    })
+}
+
+// NOTE: columns further than the window from the lighting boundary
+fn settled_columns(window: u32) -> impl Iterator<Item = u32> {
+    (0..SIZE.0).filter(move |&x| x.abs_diff(BOUNDARY) > window)
+}
+
+fn is_ink(binary: &GrayImage, x: u32) -> bool {
+    binary.get_pixel(x, SIZE.1 / 2).0[0] == 255
+}
+
+#[test]
+fn otsu_loses_the_shadowed_half() {
+    let binary = Binarizer::Otsu.binarize(&uneven_strip());
+    assert!((0..BOUNDARY).all(|x| !is_ink(&binary, x)));
+}
+
+#[test]
+fn local_thresholds_follow_the_lighting() {
+    let window = 15;
+    for binarizer in [
+        Binarizer::Sauvola { window, k: 0.2 },
+        Binarizer::Niblack { window, k: -0.2 },
+    ] {
+        let binary = binarizer.binarize(&uneven_strip());
+        for x in settled_columns(window) {
+            assert_eq!(is_ink(&binary, x), is_stroke(x), "{binarizer:?} column {x}");
+        }
+    }
+}
+
+#[test]
+fn local_thresholds_leave_flat_regions_empty() {
+    let flat = GrayImage::from_pixel(SIZE.0, SIZE.1, Luma([120]));
+    for binarizer in [
+        Binarizer::Sauvola { window: 15, k: 0.2 },
+        Binarizer::Niblack {
+            window: 15,
+            k: -0.2,
+        },
+    ] {
+        let binary = binarizer.binarize(&flat);
+        assert!(
+            binary.pixels().all(|pixel| pixel.0[0] == 0),
+            "{binarizer:?}"
+        );
+    }
+}
+
+#[test]
+fn fixed_threshold_is_exclusive() {
+    let gray = GrayImage::from_fn(3, 1, |x, _| Luma([99 + x as u8]));
+    let binary = Binarizer::Fixed(100).binarize(&gray);
+    assert_eq!(binary.as_raw(), &[0, 0, 255]);
+}
+
+const TEXT: [u8; 3] = [255, 210, 60];
+const SCENERY: [u8; 3] = [230, 230, 230];
+
+// NOTE: yellow strokes over grey scenery that is brighter than the text itself
+fn bright_scenery(text: [u8; 3]) -> RgbImage {
+    RgbImage::from_fn(SIZE.0, SIZE.1, |x, _| {
+        Rgb(if is_stroke(x) { text } else { SCENERY })
+    })
+}
+
+#[test]
+fn color_key_ignores_bright_scenery() {
+    let image = bright_scenery(TEXT);
+    let otsu = Binarizer::Otsu.binarize(&image);
+    assert!((0..SIZE.0).all(|x| is_ink(&otsu, x) != is_stroke(x)));
+
+    for space in [ColorSpace::Rgb, ColorSpace::Hsv] {
+        let binarizer = Binarizer::ColorKey {
+            color: TEXT,
+            tolerance: 0.1,
+            space,
+        };
+        let binary = binarizer.binarize(&image);
+        assert!(
+            (0..SIZE.0).all(|x| is_ink(&binary, x) == is_stroke(x)),
+            "{space:?}"
+        );
+    }
+}
+
+#[test]
+fn hsv_key_tolerates_dimmed_text() {
+    let dimmed = TEXT.map(|c| (c as f32 * 0.6) as u8);
+    let image = bright_scenery(dimmed);
+    let key = |space| Binarizer::ColorKey {
+        color: TEXT,
+        tolerance: 0.25,
+        space,
+    };
+
+    let hsv = key(ColorSpace::Hsv).binarize(&image);
+    assert!((0..SIZE.0).all(|x| is_ink(&hsv, x) == is_stroke(x)));
+    let rgb = key(ColorSpace::Rgb).binarize(&image);
+    assert!((0..SIZE.0).all(|x| !is_ink(&rgb, x)));
+}
+
+#[test]
+fn templates_are_binarized_with_their_context() {
+    let strip = uneven_strip();
+    let binarizer = Binarizer::Sauvola { window: 5, k: 0.2 };
+    let margin = binarizer.context();
+    assert_eq!(margin, 2);
+
+    // NOTE: a glyph box in the lit half, the source grown by the margin on every side
+    let (x, y, width, height) = (36, 4, 12, 4);
+    let source = imageops::crop_imm(
+        &strip,
+        x - margin,
+        y - margin,
+        width + margin * 2,
+        height + margin * 2,
+    )
+    .to_image();
+    let template = binarizer.binarize_template(&source).unwrap();
+    let region = imageops::crop_imm(&binarizer.binarize(&strip), x, y, width, height).to_image();
+    assert_eq!(template, region);
+
+    assert_eq!(Binarizer::Otsu.context(), 0);
+    assert_eq!(binarizer.binarize_template(&GrayImage::new(4, 12)), None);
+}
@@ -0,0 +1,9 @@
+use std::path::PathBuf;
+
+// NOTE: an empty directory per test and process, the test removes it when done
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("nzm-visual-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
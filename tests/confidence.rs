@@ -0,0 +1,90 @@
+use image::{GrayImage, Luma};
+use nzm_visual::capturer::Region;
+use nzm_visual::constants::{GLYPH_PASSING_CONFIDENCE, GLYPH_PASSING_MARGIN};
+use nzm_visual::matcher::Score;
+use nzm_visual::recognizer::{
+    GlyphMatch, best_glyph, bidirectional_distance_transform, match_confidence, template_match,
+};
+
+// NOTE: a 1px vertical bar at x, the rest is background
+fn bar(x: u32) -> GrayImage {
+    GrayImage::from_fn(6, 8, |px, _| Luma([if px == x { 255 } else { 0 }]))
+}
+
+const BOUNDS: Region = Region::new(2, 0, 1, 8);
+
+fn score(confidence: f32) -> Score {
+    Score {
+        confidence,
+        offset: (0, 0),
+    }
+}
+
+#[test]
+fn exact_match_is_fully_confident() {
+    let template = bar(2);
+    let (fg, bg) = bidirectional_distance_transform(&template);
+    let pixels = template.len();
+
+    assert_eq!(
+        match_confidence(template_match(&template, &fg, &bg).unwrap(), pixels),
+        1.0
+    );
+    let shifted = match_confidence(template_match(&bar(4), &fg, &bg).unwrap(), pixels);
+    assert!(shifted < 1.0 && shifted > 0.0);
+    assert_eq!(match_confidence(0, 0), 0.0);
+}
+
+#[test]
+fn margin_is_the_lead_over_the_runner_up() {
+    let best = best_glyph(
+        BOUNDS,
+        [
+            ('3', score(0.5)),
+            ('8', score(0.875)),
+            ('5', score(0.75)),
+            ('9', score(0.25)),
+        ]
+        .into_iter(),
+    )
+    .unwrap();
+    assert_eq!(best.glyph, '8');
+    assert_eq!(best.confidence, 0.875);
+    assert_eq!(best.margin, 0.875 - 0.75);
+    assert_eq!(best.bounds, BOUNDS);
+
+    // NOTE: a lone candidate leads by its whole confidence
+    assert_eq!(
+        best_glyph(BOUNDS, [('1', score(0.5))].into_iter())
+            .unwrap()
+            .margin,
+        0.5
+    );
+    assert_eq!(best_glyph(BOUNDS, std::iter::empty()), None);
+}
+
+#[test]
+fn passing_needs_both_thresholds() {
+    let at_threshold = GlyphMatch {
+        glyph: '0',
+        confidence: GLYPH_PASSING_CONFIDENCE,
+        margin: GLYPH_PASSING_MARGIN,
+        offset: (0, 0),
+        bounds: BOUNDS,
+    };
+    assert!(at_threshold.passing());
+    assert!(
+        !GlyphMatch {
+            confidence: GLYPH_PASSING_CONFIDENCE.next_down(),
+            ..at_threshold
+        }
+        .passing()
+    );
+    assert!(
+        !GlyphMatch {
+            margin: GLYPH_PASSING_MARGIN.next_down(),
+            ..at_threshold
+        }
+        .passing()
+    );
+}
@@ -0,0 +1,39 @@
+use image::{Rgba, RgbaImage};
+use nzm_visual::{constants::REGION_CHANGE_TOLERANCE, fingerprint::Fingerprint};
+
+fn flat(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_pixel(width, height, Rgba([60, 60, 60, 255]))
+}
+
+// NOTE: raises every pixel of the top left cell by delta
+fn raised(delta: u8) -> RgbaImage {
+    let mut image = flat(12, 6);
+    for y in 0..Fingerprint::BLOCK {
+        for x in 0..Fingerprint::BLOCK {
+            image.put_pixel(x, y, Rgba([60 + delta, 60 + delta, 60 + delta, 255]));
+        }
+    }
+    image
+}
+
+#[test]
+fn tolerates_noise_but_not_a_changed_cell() {
+    let base = Fingerprint::new(&flat(12, 6));
+    assert!(base.matches(&Fingerprint::new(&flat(12, 6)), 0));
+
+    // NOTE: 9 on a single pixel moves its cell mean by 1
+    let mut noisy = flat(12, 6);
+    noisy.put_pixel(0, 0, Rgba([69, 69, 69, 255]));
+    let noisy = Fingerprint::new(&noisy);
+    assert!(!base.matches(&noisy, 0));
+    assert!(base.matches(&noisy, REGION_CHANGE_TOLERANCE));
+
+    // NOTE: a changed digit moves a cell mean by about 28
+    assert!(!base.matches(&Fingerprint::new(&raised(28)), REGION_CHANGE_TOLERANCE));
+}
+
+#[test]
+fn never_matches_another_size() {
+    let base = Fingerprint::new(&flat(12, 6));
+    assert!(!base.matches(&Fingerprint::new(&flat(12, 7)), u8::MAX));
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use common::temp_dir;
+use image::RgbaImage;
+use nzm_visual::history::History;
+use nzm_visual::pipeline::Tick;
+use nzm_visual::recognizer::{Reading, Unrecognized};
+use nzm_visual::recorder::INDEX_FILE;
+
+fn tick(timestamp: Instant, sequence: u64) -> Tick {
+    Tick {
+        timestamp,
+        sequence,
+        regions: vec![RgbaImage::new(4, 2), RgbaImage::new(4, 2)],
+        money: Reading::Missing(Unrecognized::EmptyRegion),
+        attack: Reading::Missing(Unrecognized::EmptyRegion),
+        future: None,
+        duration: Duration::ZERO,
+        latency: Duration::ZERO,
+        anomaly: None,
+    }
+}
+
+fn recorded(session: &Path) -> Vec<u64> {
+    let index = std::fs::read_to_string(session.join(INDEX_FILE)).unwrap();
+    index
+        .lines()
+        .skip(1)
+        .map(|row| row.split(',').next().unwrap().parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn keeps_only_the_window() {
+    let start = Instant::now();
+    let mut history = History::new(Duration::from_secs(10));
+    for sequence in 0..3 {
+        history.push(tick(start + Duration::from_secs(sequence * 5), sequence));
+    }
+    assert_eq!(history.len(), 3);
+
+    // NOTE: 15s after the first and 10s after the second, only the first falls out
+    history.push(tick(start + Duration::from_secs(15), 3));
+    assert_eq!(history.len(), 3);
+}
+
+#[test]
+fn dumps_keep_the_window() {
+    let dir = temp_dir("history-dump");
+    let start = Instant::now();
+    let mut history = History::new(Duration::from_secs(10));
+    for sequence in 0..3 {
+        history.push(tick(start + Duration::from_secs(sequence), sequence));
+    }
+
+    let first = history.dump(&dir).unwrap();
+    assert!(first.starts_with(&dir));
+    assert!(first.to_string_lossy().ends_with("_00000002"));
+    assert_eq!(recorded(&first), [0, 1, 2]);
+    assert_eq!(history.len(), 3);
+
+    // NOTE: the oldest tick falls out of the window
+    history.push(tick(start + Duration::from_secs(11), 3));
+    let second = history.dump(&dir).unwrap();
+    assert_ne!(first, second);
+    assert_eq!(recorded(&second), [1, 2, 3]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
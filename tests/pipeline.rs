@@ -0,0 +1,86 @@
+use std::{collections::VecDeque, time::Instant};
+
+use image::{GrayImage, Rgba, RgbaImage};
+use nzm_visual::capturer::{CaptureError, Frame, FrameSource, Region};
+use nzm_visual::constants::{ATTACK_GLYPHS, MONEY_GLYPHS, MONEY_REGION, REFERENCE_GEOMETRY};
+use nzm_visual::pipeline::Pipeline;
+use nzm_visual::recognizer::{MemoryTemplates, Recognizer};
+
+// NOTE: hands out the scripted money crops, the attack crop stays flat
+struct Scripted {
+    money: VecDeque<RgbaImage>,
+    sequence: u64,
+}
+
+impl FrameSource for Scripted {
+    fn geometry(&self) -> (u32, u32) {
+        REFERENCE_GEOMETRY
+    }
+
+    fn capture(&mut self, regions: &[Region]) -> Result<Frame, CaptureError> {
+        let money = self.money.pop_front().ok_or(CaptureError::Exhausted)?;
+        let attack = &regions[1];
+        self.sequence += 1;
+        Ok(Frame {
+            regions: vec![
+                money,
+                RgbaImage::from_pixel(attack.width, attack.height, Rgba([60, 60, 60, 255])),
+            ],
+            timestamp: Instant::now(),
+            sequence: self.sequence,
+        })
+    }
+}
+
+fn recognizer() -> Recognizer {
+    let mut templates = MemoryTemplates::default();
+    for glyph in MONEY_GLYPHS {
+        templates.insert_money_glyph(glyph, GrayImage::new(4, 4));
+    }
+    for glyph in ATTACK_GLYPHS {
+        templates.insert_attack_glyph(glyph, GrayImage::new(4, 4));
+    }
+    Recognizer::new(&templates).unwrap()
+}
+
+// NOTE: a flat money crop with the top left 3x3 fingerprint cell raised by delta
+fn money(delta: u8) -> RgbaImage {
+    let region = MONEY_REGION;
+    RgbaImage::from_fn(region.width, region.height, |x, y| {
+        let value = if x < 3 && y < 3 { 60 + delta } else { 60 };
+        Rgba([value, value, value, 255])
+    })
+}
+
+// NOTE: 9 on a single pixel moves its cell mean by 1
+fn noisy() -> RgbaImage {
+    let mut money = money(0);
+    money.put_pixel(0, 0, Rgba([69, 69, 69, 255]));
+    money
+}
+
+#[test]
+fn reuses_results_until_a_cell_changes_beyond_the_tolerance() {
+    let source = Scripted {
+        // NOTE: a changed digit moves a cell mean by about 28
+        money: [money(0), money(0), noisy(), money(28)].into(),
+        sequence: 0,
+    };
+    let mut pipeline = Pipeline::new(source, recognizer());
+
+    let first = pipeline.tick().unwrap();
+    assert_eq!(pipeline.stats().unchanged, 0);
+
+    let same = pipeline.tick().unwrap();
+    assert_eq!(pipeline.stats().unchanged, 2);
+    assert_eq!(same.money, first.money);
+
+    let noisy = pipeline.tick().unwrap();
+    assert_eq!(pipeline.stats().unchanged, 4);
+    assert_eq!(noisy.money, first.money);
+
+    // NOTE: only the attack crop is reused
+    let changed = pipeline.tick().unwrap();
+    assert_eq!(pipeline.stats().unchanged, 5);
+    assert_ne!(changed.money, first.money);
+}
@@ -0,0 +1,409 @@
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+use nzm_visual::constants::{ATTACK_GLYPHS, MONEY_GLYPHS};
+use nzm_visual::matcher::{DistanceMatcher, Matcher, NccMatcher};
+use nzm_visual::recognizer::{
+    MemoryTemplates, Reading, Recognizer, Unrecognized, bidirectional_distance_transform,
+    template_match,
+};
+use nzm_visual::segmenter::Segmenter;
+
+// NOTE: 5x7 cells of 2x2 pixels, large enough to survive resampling
+const GLYPH_SIZE: (u32, u32) = (10, 14);
+
+// NOTE: an arbitrary pattern per glyph that stays distinct when shifted by a pixel,
+// the middle row gives every column ink so each glyph is one segment
+fn glyph(index: u32) -> GrayImage {
+    GrayImage::from_fn(GLYPH_SIZE.0, GLYPH_SIZE.1, |x, y| {
+        let (x, y) = (x / 2, y / 2);
+        let hash = (index * 131 + x * 31 + y * 17).wrapping_mul(2654435761) >> 16;
+        let on = y == 3 || hash.is_multiple_of(3);
+        Luma([if on { 255 } else { 0 }])
+    })
+}
+
+// NOTE: a bottom-heavy tick, narrower than any digit
+fn comma() -> GrayImage {
+    GrayImage::from_fn(4, GLYPH_SIZE.1, |x, y| {
+        Luma([if y >= 10 && (x >= 2 || y < 12) {
+            255
+        } else {
+            0
+        }])
+    })
+}
+
+// NOTE: digits share the attack glyph patterns
+fn glyph_image(c: char) -> GrayImage {
+    match c {
+        ',' => comma(),
+        c => glyph(ATTACK_GLYPHS.iter().position(|&g| g == c).unwrap() as u32),
+    }
+}
+
+fn templates() -> MemoryTemplates {
+    let mut templates = MemoryTemplates::default();
+    for money in MONEY_GLYPHS {
+        templates.insert_money_glyph(money, glyph_image(money));
+    }
+    for (index, &attack) in ATTACK_GLYPHS.iter().enumerate() {
+        templates.insert_attack_glyph(attack, glyph(index as u32));
+    }
+    templates
+}
+
+fn recognizer() -> Recognizer {
+    Recognizer::new(&templates()).unwrap()
+}
+
+// NOTE: glyphs laid out left to right, gaps[i] pixels before the i-th glyph (two by default),
+// ink and background are gray levels, at least 64 pixels wide
+fn laid_out(text: &str, gaps: &[u32], ink: u8, background: u8) -> RgbaImage {
+    let glyphs: Vec<GrayImage> = text.chars().map(glyph_image).collect();
+    let width = glyphs.iter().map(|glyph| glyph.width() + 2).sum::<u32>() + 2;
+    let mut region = RgbaImage::from_pixel(
+        width.max(64),
+        GLYPH_SIZE.1,
+        Rgba([background, background, background, 255]),
+    );
+    let mut left = 0;
+    for (i, glyph) in glyphs.iter().enumerate() {
+        left += gaps.get(i).copied().unwrap_or(2);
+        for (x, y, pixel) in glyph.enumerate_pixels() {
+            let v = if pixel.0[0] == 0 { background } else { ink };
+            region.put_pixel(left + x, y, Rgba([v, v, v, 255]));
+        }
+        left += glyph.width();
+    }
+    region
+}
+
+fn shaded_region(text: &str, ink: u8, background: u8) -> RgbaImage {
+    laid_out(text, &[], ink, background)
+}
+
+fn region(text: &str) -> RgbaImage {
+    shaded_region(text, 255, 0)
+}
+
+#[test]
+fn reads_digits_and_percent() {
+    let recognizer = recognizer();
+    for (text, level) in [("25%", 25), ("450%", 450), ("1500%", 1500)] {
+        let reading = recognizer.recognize_attack(&region(text));
+        assert!(
+            matches!(reading, Reading::Confident { .. }),
+            "{text}: {reading}"
+        );
+        assert_eq!(reading.value(), Some(level));
+        assert_eq!(reading.glyphs().last().unwrap().glyph, '%');
+    }
+}
+
+#[test]
+fn unknown_level_is_never_confident() {
+    let reading = recognizer().recognize_attack(&region("99%"));
+    assert!(
+        matches!(reading, Reading::Uncertain { value: 99, .. }),
+        "{reading}"
+    );
+}
+
+#[test]
+fn rejects_missing_or_misplaced_percent() {
+    let recognizer = recognizer();
+    for text in ["150", "%150", "1%5%"] {
+        assert_eq!(
+            recognizer.recognize_attack(&region(text)),
+            Reading::Missing(Unrecognized::Malformed),
+            "{text}"
+        );
+    }
+}
+
+#[test]
+fn ncc_reads_low_contrast_grayscale() {
+    let recognizer =
+        Recognizer::with_matchers(&templates(), NccMatcher::default(), NccMatcher::default())
+            .unwrap();
+    let reading = recognizer.recognize_attack(&shaded_region("450%", 140, 90));
+    assert!(
+        matches!(reading, Reading::Confident { value: 450, .. }),
+        "{reading}"
+    );
+    assert!(reading.confidence().unwrap() > 0.99);
+}
+
+#[test]
+fn finds_glyphs_shifted_by_a_pixel() {
+    let region = region("450%");
+    let shifted = RgbaImage::from_fn(region.width(), region.height(), |x, y| match y {
+        0 => Rgba([0, 0, 0, 255]),
+        y => *region.get_pixel(x, y - 1),
+    });
+
+    let reading = recognizer().recognize_attack(&shifted);
+    assert_eq!(reading.value(), Some(450), "{reading}");
+    assert!(reading.glyphs().iter().all(|glyph| glyph.offset == (0, 1)));
+
+    let fixed = Recognizer::with_matchers(
+        &templates(),
+        DistanceMatcher { window: 0 },
+        DistanceMatcher { window: 0 },
+    )
+    .unwrap()
+    .recognize_attack(&shifted);
+    assert!(fixed.confidence().unwrap_or(0.0) < reading.confidence().unwrap());
+}
+
+fn upscaled(region: &RgbaImage, factor: f32) -> RgbaImage {
+    let width = (region.width() as f32 * factor).round() as u32;
+    let height = (region.height() as f32 * factor).round() as u32;
+    image::imageops::resize(region, width, height, image::imageops::FilterType::Nearest)
+}
+
+#[test]
+fn reads_crops_from_a_larger_screen() {
+    let mut recognizer = recognizer();
+    recognizer.set_scale(2.0);
+    let reading = recognizer.recognize_attack(&upscaled(&region("450%"), 2.0));
+    assert!(
+        matches!(reading, Reading::Confident { value: 450, .. }),
+        "{reading}"
+    );
+}
+
+#[test]
+fn tries_nearby_scales_for_the_ui_scale() {
+    let reading = recognizer().recognize_attack(&upscaled(&region("1200%"), 1.25));
+    assert!(
+        matches!(reading, Reading::Confident { value: 1200, .. }),
+        "{reading}"
+    );
+}
+
+#[test]
+fn reads_glyphs_far_into_a_wide_region() {
+    let text = region("1500%");
+    let mut wide = RgbaImage::from_pixel(400, text.height(), Rgba([0, 0, 0, 255]));
+    image::imageops::replace(&mut wide, &text, 300, 0);
+    let reading = recognizer().recognize_attack(&wide);
+    assert!(
+        matches!(reading, Reading::Confident { value: 1500, .. }),
+        "{reading}"
+    );
+}
+
+#[test]
+fn splits_touching_money_digits() {
+    let recognizer = recognizer();
+    let pair = recognizer.recognize_money(&laid_out("12,345", &[2, 2, 2, 2, 0, 2], 255, 0));
+    assert!(
+        matches!(pair, Reading::Confident { value: 12345, .. }),
+        "{pair}"
+    );
+
+    // NOTE: whole runs of touching digits keep every digit, if not with confidence
+    let run = recognizer.recognize_money(&laid_out("12,345", &[2, 0, 2, 2, 0, 0], 255, 0));
+    assert_eq!(run.value(), Some(12345), "{run}");
+}
+
+// NOTE: a narrow 1 with a flag, and a 7 that is that same 1 stretched to full width
+fn one() -> GrayImage {
+    GrayImage::from_fn(4, GLYPH_SIZE.1, |x, y| {
+        Luma([if y < 2 || x >= 2 { 255 } else { 0 }])
+    })
+}
+
+fn seven() -> GrayImage {
+    GrayImage::from_fn(GLYPH_SIZE.0, GLYPH_SIZE.1, |x, y| {
+        Luma([if y < 2 || x >= 5 { 255 } else { 0 }])
+    })
+}
+
+#[test]
+fn keeps_narrow_glyphs_apart_from_wide_ones() {
+    let mut templates = templates();
+    templates.insert_money_glyph('1', one());
+    templates.insert_money_glyph('7', seven());
+    let recognizer = Recognizer::new(&templates).unwrap();
+
+    let mut region = RgbaImage::from_pixel(64, GLYPH_SIZE.1, Rgba([0, 0, 0, 255]));
+    let mut left = 2;
+    for glyph in [seven(), one(), seven()] {
+        for (x, y, pixel) in glyph.enumerate_pixels() {
+            let v = pixel.0[0];
+            region.put_pixel(left + x, y, Rgba([v, v, v, 255]));
+        }
+        left += glyph.width() + 2;
+    }
+
+    let reading = recognizer.recognize_money(&region);
+    assert!(
+        matches!(reading, Reading::Confident { value: 717, .. }),
+        "{reading}"
+    );
+}
+
+#[test]
+fn reads_money_with_separators() {
+    let recognizer = recognizer();
+    for (text, money) in [("1,234,567", 1234567), ("12,345", 12345), ("999", 999)] {
+        let reading = recognizer.recognize_money(&region(text));
+        assert!(
+            matches!(reading, Reading::Confident { value, .. } if value == money),
+            "{text}: {reading}"
+        );
+    }
+}
+
+#[test]
+fn components_read_separators() {
+    let components = |separators| Segmenter::Components {
+        min_area: 4,
+        min_width: 2,
+        min_height: 8,
+        max_aspect: 2.0,
+        separators,
+    };
+    let read = |separators| {
+        recognizer()
+            .with_segmenters(components(separators), Segmenter::Projection)
+            .recognize_money(&region("12,345"))
+    };
+
+    let reading = read(true);
+    assert!(
+        matches!(reading, Reading::Confident { value: 12345, .. }),
+        "{reading}"
+    );
+    // NOTE: without them the short comma is dropped and the grouping gives it away
+    assert_eq!(read(false), Reading::Missing(Unrecognized::Malformed));
+}
+
+#[test]
+fn rejects_misplaced_separators() {
+    let recognizer = recognizer();
+    // NOTE: the HUD always groups, so 12345 is a dropped or misread separator
+    for text in ["1,23,4", "1234,567", ",123", "123,", "12345", "1234"] {
+        assert_eq!(
+            recognizer.recognize_money(&region(text)),
+            Reading::Missing(Unrecognized::Malformed),
+            "{text}"
+        );
+    }
+}
+
+// NOTE: a 2x2 speck of ink in the last two columns, rows y and y + 1
+fn specked(mut region: RgbaImage, y: u32) -> RgbaImage {
+    let x = region.width() - 2;
+    for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+        region.put_pixel(x + dx, y + dy, Rgba([255, 255, 255, 255]));
+    }
+    region
+}
+
+#[test]
+fn drops_specks_next_to_money() {
+    let recognizer = recognizer();
+    // NOTE: unlike a comma these do not sit on the baseline
+    for y in [0, 3, 6] {
+        let reading = recognizer.recognize_money(&specked(region("1,234"), y));
+        assert!(
+            matches!(reading, Reading::Confident { value: 1234, .. }),
+            "{y}: {reading}"
+        );
+    }
+}
+
+#[test]
+fn reads_template_packs_without_a_separator() {
+    let mut templates = MemoryTemplates::default();
+    for money in MONEY_GLYPHS.into_iter().filter(|&glyph| glyph != ',') {
+        templates.insert_money_glyph(money, glyph_image(money));
+    }
+    for (index, &attack) in ATTACK_GLYPHS.iter().enumerate() {
+        templates.insert_attack_glyph(attack, glyph(index as u32));
+    }
+    let recognizer = Recognizer::new(&templates).unwrap();
+    assert!(!recognizer.separators());
+    assert!(self::recognizer().separators());
+
+    // NOTE: the grouping is not checked, and a separator is as narrow as a speck
+    for (text, money) in [("12345", 12345), ("1,234", 1234)] {
+        let reading = recognizer.recognize_money(&specked(region(text), 6));
+        assert!(
+            matches!(reading, Reading::Confident { value, .. } if value == money),
+            "{text}: {reading}"
+        );
+    }
+}
+
+#[test]
+fn prefers_a_value_over_the_nearest_scale() {
+    // NOTE: 99% is never confident, so every factor is tried and none wins outright
+    let halved = upscaled(&region("99%"), 0.5);
+    let nearest = recognizer()
+        .with_scales(vec![1.25])
+        .recognize_attack(&halved);
+    assert!(matches!(nearest, Reading::Missing(_)), "{nearest}");
+
+    let reading = recognizer()
+        .with_scales(vec![1.25, 0.5])
+        .recognize_attack(&halved);
+    assert!(
+        matches!(reading, Reading::Uncertain { value: 99, .. }),
+        "{reading}"
+    );
+}
+
+#[test]
+fn prefers_the_more_confident_scale() {
+    // NOTE: neither factor undoes the 0.8 shrink, 1.25 is tried first but 0.7 matches better
+    let shrunk = upscaled(&region("99%"), 0.8);
+    let read = |scales: Vec<f32>| recognizer().with_scales(scales).recognize_attack(&shrunk);
+    let (nearer, farther) = (read(vec![1.25]), read(vec![0.7]));
+    assert!(!matches!(nearer, Reading::Confident { .. }), "{nearer}");
+    assert!(!matches!(farther, Reading::Confident { .. }), "{farther}");
+    assert!(farther.confidence() > nearer.confidence());
+    assert_eq!(read(vec![1.25, 0.7]), farther);
+    assert_eq!(read(vec![0.7, 1.25]), farther);
+}
+
+#[test]
+fn ignores_scales_without_a_crop_size() {
+    let recognizer = recognizer().with_scales(vec![0.0, -1.0, f32::NAN, f32::INFINITY, 1.0]);
+    let reading = recognizer.recognize_attack(&region("450%"));
+    assert!(
+        matches!(reading, Reading::Confident { value: 450, .. }),
+        "{reading}"
+    );
+
+    // NOTE: with no usable scale the crop is read as is
+    let mut recognizer = recognizer.with_scales(vec![1.0]);
+    recognizer.set_scale(0.0);
+    let reading = recognizer.recognize_attack(&region("450%"));
+    assert!(
+        matches!(reading, Reading::Confident { value: 450, .. }),
+        "{reading}"
+    );
+}
+
+#[test]
+fn template_match_rejects_other_sizes() {
+    let (fg, bg) = bidirectional_distance_transform(&glyph(4));
+    assert_eq!(template_match(&glyph(4), &fg, &bg), Some(0));
+    let narrow = GrayImage::new(GLYPH_SIZE.0 - 1, GLYPH_SIZE.1);
+    assert_eq!(template_match(&narrow, &fg, &bg), None);
+}
+
+#[test]
+fn ncc_rejects_other_sizes() {
+    let matcher = NccMatcher { window: 0 };
+    let template = matcher.prepare(&glyph(4));
+    assert!(matcher.score(&glyph(4), &template).confidence > 0.99);
+    // NOTE: the same pixels one row short, a prefix of the template
+    let short = GrayImage::from_fn(GLYPH_SIZE.0, GLYPH_SIZE.1 - 1, |x, y| {
+        *glyph(4).get_pixel(x, y)
+    });
+    assert_eq!(matcher.score(&short, &template).confidence, 0.0);
+}
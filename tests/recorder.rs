@@ -0,0 +1,85 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::temp_dir;
+use image::{Rgba, RgbaImage};
+use nzm_visual::{
+    capturer::Region,
+    pipeline::Tick,
+    recognizer::{GlyphMatch, Reading, Unrecognized},
+    recorder::{INDEX_FILE, REGION_DIRS, Recorder},
+};
+
+// NOTE: 5x8 glyph boxes one pixel below the top of the crop
+fn glyph(glyph: char, confidence: f32, margin: f32, x: u32) -> GlyphMatch {
+    GlyphMatch {
+        glyph,
+        confidence,
+        margin,
+        offset: (0, 0),
+        bounds: Region::new(x, 1, 5, 8),
+    }
+}
+
+fn tick(
+    timestamp: Instant,
+    sequence: u64,
+    money: Reading<u32>,
+    future: Option<(u32, u16)>,
+) -> Tick {
+    Tick {
+        timestamp,
+        sequence,
+        regions: vec![
+            RgbaImage::from_pixel(12, 5, Rgba([255, 255, 255, 255])),
+            RgbaImage::from_pixel(7, 4, Rgba([0, 0, 0, 255])),
+        ],
+        money,
+        attack: Reading::Uncertain {
+            value: 2,
+            glyphs: vec![glyph('2', 0.5, 0.25, 0)],
+        },
+        future,
+        duration: Duration::from_micros(250),
+        latency: Duration::from_millis(1),
+        anomaly: None,
+    }
+}
+
+#[test]
+fn records_crops_and_index_rows() {
+    let dir = temp_dir("recorder");
+    let start = Instant::now();
+    let mut recorder = Recorder::create(&dir).unwrap();
+    let confident = Reading::Confident {
+        value: 10,
+        glyphs: vec![glyph('1', 0.95, 0.5, 0), glyph('0', 0.9, 0.125, 6)],
+    };
+    recorder
+        .record(&tick(start, 4, confident, Some((110, 3))))
+        .unwrap();
+    let missing = Reading::Missing(Unrecognized::NoDigits);
+    recorder
+        .record(&tick(start + Duration::from_millis(40), 5, missing, None))
+        .unwrap();
+
+    let index = std::fs::read_to_string(dir.join(INDEX_FILE)).unwrap();
+    let rows: Vec<&str> = index.lines().skip(1).collect();
+    assert_eq!(
+        rows,
+        [
+            "4,0,10,0.900,confident,\"1:0.950:0.500:0:1:5:8;0:0.900:0.125:6:1:5:8\",2,0.500,uncertain,\"2:0.500:0.250:0:1:5:8\",110,3,250",
+            "5,40,,,NoDigits,,2,0.500,uncertain,\"2:0.500:0.250:0:1:5:8\",,,250",
+        ]
+    );
+
+    for (region, dimensions) in REGION_DIRS.iter().zip([(12, 5), (7, 4)]) {
+        for sequence in [4, 5] {
+            let path = dir.join(region).join(format!("{sequence:08}.png"));
+            assert_eq!(image::image_dimensions(&path).unwrap(), dimensions);
+        }
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,229 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use nzm_visual::capturer::{
+    CaptureError, Frame, FrameSource, Region,
+    recovery::{Backoff, CaptureState, Recovering},
+};
+
+// NOTE: every capture pops the next scripted outcome, an empty script keeps producing frames
+struct Scripted {
+    script: Rc<RefCell<VecDeque<Result<(), CaptureError>>>>,
+}
+
+impl FrameSource for Scripted {
+    fn geometry(&self) -> (u32, u32) {
+        (2560, 1600)
+    }
+
+    fn capture(&mut self, regions: &[Region]) -> Result<Frame, CaptureError> {
+        self.script.borrow_mut().pop_front().unwrap_or(Ok(()))?;
+        Ok(Frame {
+            regions: regions
+                .iter()
+                .map(|r| image::RgbaImage::new(r.width, r.height))
+                .collect(),
+            timestamp: Instant::now(),
+            sequence: 0,
+        })
+    }
+}
+
+struct Harness {
+    captures: Rc<RefCell<VecDeque<Result<(), CaptureError>>>>,
+    creations: Rc<RefCell<VecDeque<Result<(), CaptureError>>>>,
+    created: Rc<RefCell<u32>>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        Self {
+            captures: Rc::default(),
+            creations: Rc::default(),
+            created: Rc::default(),
+        }
+    }
+
+    fn source(
+        &self,
+        backoff: Backoff,
+    ) -> Recovering<Scripted, impl FnMut() -> Result<Scripted, CaptureError>> {
+        let (captures, creations, created) = (
+            self.captures.clone(),
+            self.creations.clone(),
+            self.created.clone(),
+        );
+        Recovering::new(
+            move || {
+                creations.borrow_mut().pop_front().unwrap_or(Ok(()))?;
+                *created.borrow_mut() += 1;
+                Ok(Scripted {
+                    script: captures.clone(),
+                })
+            },
+            backoff,
+        )
+    }
+}
+
+const REGIONS: [Region; 1] = [Region::new(0, 0, 4, 2)];
+
+fn instant() -> Backoff {
+    Backoff {
+        initial: Duration::ZERO,
+        max: Duration::ZERO,
+        max_attempts: None,
+    }
+}
+
+#[test]
+fn recreates_source_after_lost_device() {
+    let harness = Harness::new();
+    let mut source = harness.source(instant());
+
+    assert!(source.capture(&REGIONS).is_ok());
+    assert_eq!(source.state(), CaptureState::Healthy);
+
+    harness
+        .captures
+        .borrow_mut()
+        .push_back(Err(CaptureError::Lost("access lost".to_string())));
+    assert!(matches!(
+        source.capture(&REGIONS),
+        Err(CaptureError::Lost(_))
+    ));
+    assert!(matches!(
+        source.state(),
+        CaptureState::Retrying { attempt: 1, .. }
+    ));
+
+    let frame = source.capture(&REGIONS).unwrap();
+    assert_eq!(frame.regions[0].dimensions(), (4, 2));
+    assert_eq!(source.state(), CaptureState::Healthy);
+    assert_eq!(*harness.created.borrow(), 2);
+}
+
+#[test]
+fn timeout_does_not_recreate_source() {
+    let harness = Harness::new();
+    let mut source = harness.source(instant());
+
+    harness
+        .captures
+        .borrow_mut()
+        .extend([Err(CaptureError::Timeout), Err(CaptureError::Timeout)]);
+    assert!(matches!(
+        source.capture(&REGIONS),
+        Err(CaptureError::Timeout)
+    ));
+    assert!(matches!(
+        source.capture(&REGIONS),
+        Err(CaptureError::Timeout)
+    ));
+    assert!(source.capture(&REGIONS).is_ok());
+
+    assert_eq!(source.state(), CaptureState::Healthy);
+    assert_eq!(*harness.created.borrow(), 1);
+}
+
+#[test]
+fn backs_off_exponentially_while_creation_fails() {
+    let harness = Harness::new();
+    let mut source = harness.source(Backoff {
+        initial: Duration::from_millis(2),
+        max: Duration::from_millis(8),
+        max_attempts: None,
+    });
+
+    harness
+        .creations
+        .borrow_mut()
+        .extend((0..5).map(|_| Err(CaptureError::Lost("no output".to_string()))));
+
+    let mut delays = Vec::new();
+    while delays.len() < 5 {
+        match source.capture(&REGIONS) {
+            Err(CaptureError::Lost(_)) => match source.state() {
+                CaptureState::Retrying { attempt, delay } => {
+                    assert_eq!(attempt as usize, delays.len() + 1);
+                    delays.push(delay);
+                }
+                state => panic!("unexpected state {state:?}"),
+            },
+            Err(CaptureError::Timeout) => std::thread::sleep(Duration::from_millis(1)),
+            other => panic!("unexpected result {:?}", other.err()),
+        }
+    }
+
+    assert_eq!(delays, [2, 4, 8, 8, 8].map(Duration::from_millis).to_vec());
+
+    while source.capture(&REGIONS).is_err() {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(source.state(), CaptureState::Healthy);
+    assert_eq!(*harness.created.borrow(), 1);
+}
+
+#[test]
+fn fatal_error_stops_retrying() {
+    let harness = Harness::new();
+    let mut source = harness.source(instant());
+
+    harness
+        .creations
+        .borrow_mut()
+        .push_back(Err(CaptureError::Fatal("unsupported".to_string())));
+    assert!(matches!(
+        source.capture(&REGIONS),
+        Err(CaptureError::Fatal(_))
+    ));
+    assert_eq!(source.state(), CaptureState::Failed);
+
+    assert!(matches!(
+        source.capture(&REGIONS),
+        Err(CaptureError::Fatal(_))
+    ));
+    assert_eq!(*harness.created.borrow(), 0);
+}
+
+#[test]
+fn gives_up_after_max_attempts() {
+    let harness = Harness::new();
+    let mut source = harness.source(Backoff {
+        max_attempts: Some(2),
+        ..instant()
+    });
+
+    harness
+        .creations
+        .borrow_mut()
+        .extend((0..3).map(|_| Err(CaptureError::Lost("no output".to_string()))));
+
+    for attempt in 1..=2 {
+        assert!(source.capture(&REGIONS).is_err());
+        assert!(matches!(
+            source.state(),
+            CaptureState::Retrying { attempt: a, .. } if a == attempt
+        ));
+    }
+    assert!(source.capture(&REGIONS).is_err());
+    assert_eq!(source.state(), CaptureState::Failed);
+}
+
+#[test]
+fn sequence_continues_across_recreated_sources() {
+    let harness = Harness::new();
+    let mut source = harness.source(instant());
+
+    assert_eq!(source.capture(&REGIONS).unwrap().sequence, 0);
+    harness
+        .captures
+        .borrow_mut()
+        .push_back(Err(CaptureError::Lost("access lost".to_string())));
+    assert!(source.capture(&REGIONS).is_err());
+    assert_eq!(source.capture(&REGIONS).unwrap().sequence, 1);
+}
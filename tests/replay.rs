@@ -0,0 +1,89 @@
+mod common;
+
+use std::{
+    fs::File,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use common::temp_dir;
+use image::{Rgba, RgbaImage};
+use nzm_visual::capturer::{
+    CaptureError, FrameSource, Region,
+    replay::{ReplayOrder, Replayer},
+};
+
+// NOTE: a flat screenshot of the given gray level, modified secs after the epoch
+fn screenshot(dir: &Path, name: &str, value: u8, secs: u64) {
+    let path = dir.join(name);
+    RgbaImage::from_pixel(16, 8, Rgba([value, value, value, 255]))
+        .save(&path)
+        .unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+        .unwrap();
+}
+
+const REGIONS: [Region; 1] = [Region::new(0, 0, 4, 4)];
+
+// NOTE: (sequence, gray level) of every frame until the replay runs out
+fn values(replayer: &mut Replayer) -> Vec<(u64, u8)> {
+    let mut values = Vec::new();
+    loop {
+        match replayer.capture(&REGIONS) {
+            Ok(frame) => {
+                assert_eq!(frame.regions[0].dimensions(), (4, 4));
+                values.push((frame.sequence, frame.regions[0].get_pixel(0, 0).0[0]));
+            }
+            Err(CaptureError::Exhausted) => return values,
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+#[test]
+fn orders_by_name_or_timestamp() {
+    let dir = temp_dir("replay-order");
+    screenshot(&dir, "a.png", 1, 2_000);
+    screenshot(&dir, "b.png", 2, 1_000);
+
+    let mut by_name = Replayer::new(&dir, ReplayOrder::Name).unwrap();
+    assert_eq!(by_name.geometry(), (16, 8));
+    assert_eq!(values(&mut by_name), [(0, 1), (1, 2)]);
+    let mut by_time = Replayer::new(&dir, ReplayOrder::Timestamp).unwrap();
+    assert_eq!(values(&mut by_time), [(0, 2), (1, 1)]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn skips_unreadable_files_but_not_their_sequence() {
+    let dir = temp_dir("replay-unreadable");
+    screenshot(&dir, "a.png", 1, 1_000);
+    std::fs::write(dir.join("b.png"), b"not a png").unwrap();
+    screenshot(&dir, "c.png", 3, 3_000);
+    std::fs::write(dir.join("notes.txt"), b"not a screenshot").unwrap();
+
+    let mut replayer = Replayer::new(&dir, ReplayOrder::Name).unwrap();
+    assert_eq!(replayer.remaining(), 3);
+    // NOTE: the unreadable file still takes a sequence number
+    assert_eq!(values(&mut replayer), [(0, 1), (2, 3)]);
+    assert_eq!(replayer.current(), None);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn ignores_frame_rates_without_an_interval() {
+    let dir = temp_dir("replay-rate");
+    screenshot(&dir, "a.png", 1, 1_000);
+
+    for fps in [0.0, -30.0, f64::NAN, f64::MIN_POSITIVE] {
+        let mut replayer = Replayer::new(&dir, ReplayOrder::Name)
+            .unwrap()
+            .with_frame_rate(fps);
+        assert_eq!(values(&mut replayer), [(0, 1)], "{fps}");
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
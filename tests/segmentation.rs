@@ -0,0 +1,99 @@
+use image::{GrayImage, Luma};
+use nzm_visual::capturer::Region;
+use nzm_visual::segmenter::{Segmenter, vertical_digit_divide};
+
+// NOTE: ink in the given column ranges, everything else background
+fn columns(width: u32, ink: &[(u32, u32)]) -> GrayImage {
+    GrayImage::from_fn(width, 8, |x, _| {
+        let on = ink.iter().any(|&(begin, end)| (begin..end).contains(&x));
+        Luma([if on { 255 } else { 0 }])
+    })
+}
+
+#[test]
+fn keeps_columns_past_255() {
+    let ink = [(10, 20), (250, 262), (300, 310), (1000, 1012)];
+    assert_eq!(vertical_digit_divide(&columns(1200, &ink)), ink);
+}
+
+#[test]
+fn keeps_a_glyph_touching_the_right_edge() {
+    let binary = columns(600, &[(100, 110), (590, 600)]);
+    assert_eq!(vertical_digit_divide(&binary), [(100, 110), (590, 600)]);
+}
+
+#[test]
+fn keeps_comma_wide_runs() {
+    let ink = [(280, 290), (292, 295), (297, 307)];
+    assert_eq!(vertical_digit_divide(&columns(400, &ink)), ink);
+}
+
+const COMPONENTS: Segmenter = Segmenter::Components {
+    min_area: 6,
+    min_width: 2,
+    min_height: 5,
+    max_aspect: 2.0,
+    separators: false,
+};
+
+fn boxes(width: u32, height: u32, ink: &[Region]) -> GrayImage {
+    GrayImage::from_fn(width, height, |x, y| {
+        let on = ink
+            .iter()
+            .any(|r| (r.x..r.x + r.width).contains(&x) && (r.y..r.y + r.height).contains(&y));
+        Luma([if on { 255 } else { 0 }])
+    })
+}
+
+#[test]
+fn components_ignore_a_speck_bridging_two_digits() {
+    let digits = [Region::new(2, 3, 5, 8), Region::new(9, 3, 5, 8)];
+    let speck = Region::new(6, 0, 4, 1);
+    let binary = boxes(16, 12, &[digits[0], digits[1], speck]);
+
+    assert_eq!(Segmenter::Projection.segment(&binary).len(), 1);
+    assert_eq!(COMPONENTS.segment(&binary), digits);
+}
+
+#[test]
+fn components_drop_short_noise() {
+    let digit = Region::new(2, 2, 5, 8);
+    let noise = Region::new(10, 9, 4, 3);
+    let binary = boxes(16, 12, &[digit, noise]);
+
+    assert_eq!(Segmenter::Projection.segment(&binary).len(), 2);
+    assert_eq!(COMPONENTS.segment(&binary), [digit]);
+}
+
+#[test]
+fn components_merge_parts_overlapping_in_x() {
+    // NOTE: roughly a %, two rings and a stroke between them, none of them touching
+    let upper = Region::new(0, 0, 3, 3);
+    let stroke = Region::new(2, 4, 3, 4);
+    let lower = Region::new(4, 9, 3, 3);
+    let binary = boxes(12, 14, &[upper, stroke, lower]);
+
+    assert_eq!(COMPONENTS.segment(&binary), [Region::new(0, 0, 7, 12)]);
+}
+
+#[test]
+fn components_keep_separators_on_the_baseline() {
+    let digits = [Region::new(2, 1, 5, 8), Region::new(11, 1, 5, 8)];
+    let comma = Region::new(8, 7, 2, 3);
+    let speck = Region::new(18, 1, 2, 3);
+    let binary = boxes(22, 12, &[digits[0], digits[1], comma, speck]);
+
+    assert_eq!(COMPONENTS.segment(&binary), digits);
+    let separators = Segmenter::Components {
+        min_area: 6,
+        min_width: 2,
+        min_height: 5,
+        max_aspect: 2.0,
+        separators: true,
+    };
+    // NOTE: the comma box spans the digit rows, the speck up high is still dropped
+    assert_eq!(
+        separators.segment(&binary),
+        [digits[0], Region::new(8, 1, 2, 8), digits[1]]
+    );
+}
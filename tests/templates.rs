@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use image::GrayImage;
+use nzm_visual::constants::{ATTACK_GLYPHS, MONEY_GLYPHS};
+use nzm_visual::recognizer::{MemoryTemplates, Recognizer, TemplateIssue, TemplateSource};
+
+#[test]
+fn reports_every_problem_with_its_path() {
+    let mut templates = MemoryTemplates::default();
+    for glyph in MONEY_GLYPHS
+        .into_iter()
+        .filter(|glyph| !['3', '7'].contains(glyph))
+    {
+        // NOTE: the first glyph sets the height every other glyph must share
+        let height = if glyph == '5' { 6 } else { 8 };
+        templates.insert_money_glyph(glyph, GrayImage::new(4, height));
+    }
+    for glyph in ATTACK_GLYPHS {
+        templates.insert_attack_glyph(glyph, GrayImage::new(4, 8));
+    }
+
+    let Err(error) = Recognizer::new(&templates) else {
+        panic!("模板有问题时不应加载成功");
+    };
+    let problems: Vec<(PathBuf, &TemplateIssue)> = error
+        .problems
+        .iter()
+        .map(|problem| (problem.path.clone(), &problem.issue))
+        .collect();
+    assert_eq!(problems.len(), 3);
+
+    assert_eq!(problems[0].0, templates.money_glyph_path('3'));
+    assert!(matches!(problems[0].1, TemplateIssue::Missing));
+    assert_eq!(problems[1].0, templates.money_glyph_path('5'));
+    assert!(matches!(
+        problems[1].1,
+        TemplateIssue::WrongSize {
+            expected: (4, 8),
+            actual: (4, 6),
+        }
+    ));
+    assert_eq!(problems[2].0, templates.money_glyph_path('7'));
+    assert!(matches!(problems[2].1, TemplateIssue::Missing));
+}
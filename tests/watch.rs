@@ -0,0 +1,77 @@
+mod common;
+
+use common::temp_dir;
+use image::{Rgba, RgbaImage};
+use nzm_visual::capturer::{CaptureError, FrameSource, Region, watch::Watcher};
+
+fn screenshot(value: u8) -> RgbaImage {
+    RgbaImage::from_pixel(32, 16, Rgba([value, value, value, 255]))
+}
+
+#[test]
+fn feeds_only_new_screenshots_in_order() {
+    let dir = temp_dir("watch-new");
+    screenshot(1).save(dir.join("old.png")).unwrap();
+
+    let mut watcher = Watcher::new(&dir).unwrap();
+    let regions = [Region::new(4, 4, 8, 4)];
+    assert!(matches!(
+        watcher.capture(&regions),
+        Err(CaptureError::Timeout)
+    ));
+
+    screenshot(2).save(dir.join("a.png")).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    screenshot(3).save(dir.join("b.png")).unwrap();
+
+    let first = watcher.capture(&regions).unwrap();
+    assert_eq!(watcher.current(), Some(dir.join("a.png").as_path()));
+    assert_eq!(first.regions[0].dimensions(), (8, 4));
+    assert_eq!(first.regions[0].get_pixel(0, 0).0, [2, 2, 2, 255]);
+
+    let second = watcher.capture(&regions).unwrap();
+    assert_eq!(second.regions[0].get_pixel(0, 0).0, [3, 3, 3, 255]);
+    assert_eq!(second.sequence, first.sequence + 1);
+    assert_eq!(watcher.geometry(), (32, 16));
+
+    assert!(matches!(
+        watcher.capture(&regions),
+        Err(CaptureError::Timeout)
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn waits_for_partially_written_files() {
+    let dir = temp_dir("watch-partial");
+    let mut watcher = Watcher::new(&dir).unwrap();
+    let regions = [Region::new(0, 0, 4, 4)];
+
+    std::fs::write(dir.join("shot.png"), b"\x89PNG").unwrap();
+    assert!(matches!(
+        watcher.capture(&regions),
+        Err(CaptureError::Timeout)
+    ));
+
+    screenshot(7).save(dir.join("shot.png")).unwrap();
+    let frame = watcher.capture(&regions).unwrap();
+    assert_eq!(frame.regions[0].get_pixel(0, 0).0, [7, 7, 7, 255]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn feeds_jpg_screenshots() {
+    let dir = temp_dir("watch-jpg");
+    let mut watcher = Watcher::new(&dir).unwrap();
+    let regions = [Region::new(0, 0, 4, 4)];
+
+    image::DynamicImage::ImageRgba8(screenshot(120))
+        .to_rgb8()
+        .save(dir.join("steam.jpg"))
+        .unwrap();
+    let frame = watcher.capture(&regions).unwrap();
+    assert_eq!(watcher.current(), Some(dir.join("steam.jpg").as_path()));
+    // NOTE: jpg is lossy, a flat image comes back within a level or two
+    assert!(frame.regions[0].get_pixel(0, 0).0[0].abs_diff(120) <= 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}